@@ -195,118 +195,605 @@ The sum of these signal strengths is 13140.
 Find the signal strength during the 20th, 60th, 100th, 140th, 180th, and 220th cycles. What is the sum of these six signal strengths?
 */
 
-fn verify_signal_strength(
-    number_of_cycles: &usize,
-    x_register: &i64,
-    signal_strenghts: &mut Vec<i64>,
-) {
-    match number_of_cycles {
-        20 => signal_strenghts.push(20 * x_register),
-        60 => signal_strenghts.push(60 * x_register),
-        100 => signal_strenghts.push(100 * x_register),
-        140 => signal_strenghts.push(140 * x_register),
-        180 => signal_strenghts.push(180 * x_register),
-        220 => signal_strenghts.push(220 * x_register),
-        _ => {}
+use crate::image::{self, ImageFormat};
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{BufRead, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Instruction {
+    Noop,
+    Addx(i64),
+}
+
+impl Instruction {
+    fn cycles(&self) -> usize {
+        match self {
+            Instruction::Noop => 1,
+            Instruction::Addx(_) => 2,
+        }
     }
 }
 
-pub fn part1(input: &str) -> Result<String, String> {
-    let mut number_of_cycles: usize = 0;
-    let mut x_register: i64 = 1;
-    let mut signal_strenghts: Vec<i64> = Vec::new();
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Noop => write!(f, "noop"),
+            Instruction::Addx(value) => write!(f, "addx {}", value),
+        }
+    }
+}
+
+pub fn parse_program(input: &str) -> Vec<Instruction> {
+    let mut program: Vec<Instruction> = Vec::new();
 
     for line in input.lines() {
         let instruction = line.split_ascii_whitespace().collect::<Vec<&str>>();
 
         match instruction.len() {
-            1 => {
-                number_of_cycles += 1;
-                verify_signal_strength(&number_of_cycles, &x_register, &mut signal_strenghts);
-            }
+            1 => program.push(Instruction::Noop),
             2 => match instruction[1].parse::<i64>() {
-                Ok(number) => {
-                    for cycle in 0..2 {
-                        number_of_cycles += 1;
-
-                        verify_signal_strength(
-                            &number_of_cycles,
-                            &x_register,
-                            &mut signal_strenghts,
-                        );
-
-                        if cycle == 1 {
-                            x_register += number;
-                        }
-                    }
-                }
+                Ok(number) => program.push(Instruction::Addx(number)),
                 Err(_) => continue,
             },
             _ => continue,
         }
     }
 
+    program
+}
+
+pub struct Cpu {
+    program: Vec<Instruction>,
+    instruction_pointer: usize,
+    instruction_cycle: usize,
+    cycle: usize,
+    x_register: i64,
+}
+
+impl Cpu {
+    pub fn new(program: Vec<Instruction>) -> Self {
+        Self {
+            program,
+            instruction_pointer: 0,
+            instruction_cycle: 0,
+            cycle: 0,
+            x_register: 1,
+        }
+    }
+
+    pub fn number_of_cycles(&self) -> usize {
+        self.cycle
+    }
+
+    pub fn x_register(&self) -> i64 {
+        self.x_register
+    }
+
+    pub fn instruction_pointer(&self) -> usize {
+        self.instruction_pointer
+    }
+
+    pub fn current_instruction(&self) -> Option<Instruction> {
+        self.program.get(self.instruction_pointer).copied()
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.instruction_pointer >= self.program.len()
+    }
+
+    // Whether the next cycle is the first cycle of the current instruction.
+    pub fn is_at_instruction_start(&self) -> bool {
+        self.instruction_cycle == 0
+    }
+}
+
+impl Iterator for Cpu {
+    // Cycle number and value of the X register during that cycle.
+    type Item = (usize, i64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let instruction = self.current_instruction()?;
+        let x_register_during_cycle = self.x_register;
+
+        self.cycle += 1;
+        self.instruction_cycle += 1;
+
+        if self.instruction_cycle == instruction.cycles() {
+            if let Instruction::Addx(number) = instruction {
+                self.x_register += number;
+            }
+
+            self.instruction_pointer += 1;
+            self.instruction_cycle = 0;
+        }
+
+        Some((self.cycle, x_register_during_cycle))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrtConfig {
+    pub width: usize,
+    pub height: usize,
+    pub sprite_width: usize,
+    pub sample_start: usize,
+    pub sample_interval: usize,
+}
+
+impl Default for CrtConfig {
+    fn default() -> Self {
+        Self {
+            width: 40,
+            height: 6,
+            sprite_width: 3,
+            sample_start: 20,
+            sample_interval: 40,
+        }
+    }
+}
+
+impl CrtConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            CrtConfig { width: 0, .. } => Err("CRT width must be at least 1".to_string()),
+            CrtConfig { height: 0, .. } => Err("CRT height must be at least 1".to_string()),
+            CrtConfig {
+                sprite_width: 0, ..
+            } => Err("Sprite width must be at least 1".to_string()),
+            CrtConfig {
+                sample_start: 0, ..
+            } => Err("Sample start must be at least 1".to_string()),
+            CrtConfig {
+                sample_interval: 0, ..
+            } => Err("Sample interval must be at least 1".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn number_of_pixels(&self) -> usize {
+        self.width * self.height
+    }
+
+    // Sampled cycles are the ones falling within a single frame.
+    pub fn is_sampled_cycle(&self, number_of_cycles: usize) -> bool {
+        number_of_cycles >= self.sample_start
+            && number_of_cycles <= self.number_of_pixels()
+            && (number_of_cycles - self.sample_start).is_multiple_of(self.sample_interval)
+    }
+
+    // First and last columns covered by a sprite centered on the X register.
+    pub fn sprite(&self, x_register: i64) -> (i64, i64) {
+        let sprite_begin = x_register - (self.sprite_width as i64 - 1) / 2;
+
+        (sprite_begin, sprite_begin + self.sprite_width as i64 - 1)
+    }
+}
+
+fn verify_signal_strength(
+    number_of_cycles: &usize,
+    x_register: &i64,
+    config: &CrtConfig,
+    signal_strenghts: &mut Vec<i64>,
+) {
+    if config.is_sampled_cycle(*number_of_cycles) {
+        signal_strenghts.push(*number_of_cycles as i64 * x_register);
+    }
+}
+
+pub fn part1_with_config(input: &str, config: &CrtConfig) -> Result<String, String> {
+    let mut signal_strenghts: Vec<i64> = Vec::new();
+
+    config.validate()?;
+
+    for (number_of_cycles, x_register) in Cpu::new(parse_program(input)) {
+        verify_signal_strength(
+            &number_of_cycles,
+            &x_register,
+            config,
+            &mut signal_strenghts,
+        );
+    }
+
     Ok(signal_strenghts.iter().sum::<i64>().to_string())
 }
 
+pub fn part1(input: &str) -> Result<String, String> {
+    part1_with_config(input, &CrtConfig::default())
+}
+
 fn draw_pixel(
     sprite_begin: &i64,
     sprite_end: &i64,
     number_of_cycles: &i64,
+    config: &CrtConfig,
     pixels: &mut Vec<&str>,
 ) {
-    if (number_of_cycles % 40 >= *sprite_begin) && (number_of_cycles % 40 <= *sprite_end) {
+    let width = config.width as i64;
+
+    if *number_of_cycles >= config.number_of_pixels() as i64 {
+        return;
+    }
+
+    if (number_of_cycles % width >= *sprite_begin) && (number_of_cycles % width <= *sprite_end) {
         pixels.push("#");
     } else {
         pixels.push(".");
     }
 
-    if (number_of_cycles + 1) % 40 == 0 {
+    if (number_of_cycles + 1) % width == 0 {
         pixels.push("\n");
     }
 }
 
-pub fn part2(input: &str) -> Result<String, String> {
-    let mut number_of_cycles: i64 = 0;
-    let mut x_register: i64 = 1;
-    let mut sprite_begin: i64 = x_register - 1;
-    let mut sprite_end: i64 = x_register + 1;
+// Runs the program once and returns the pixels drawn on the screen, with a newline after every row.
+fn draw_screen(input: &str, config: &CrtConfig) -> Result<Vec<&'static str>, String> {
     let mut pixels: Vec<&str> = Vec::new();
 
-    for line in input.lines() {
-        let instruction = line.split_ascii_whitespace().collect::<Vec<&str>>();
+    config.validate()?;
 
-        match instruction.len() {
-            1 => {
-                draw_pixel(&sprite_begin, &sprite_end, &number_of_cycles, &mut pixels);
-                number_of_cycles += 1;
+    for (number_of_cycles, x_register) in Cpu::new(parse_program(input)) {
+        let (sprite_begin, sprite_end) = config.sprite(x_register);
+
+        draw_pixel(
+            &sprite_begin,
+            &sprite_end,
+            &(number_of_cycles as i64 - 1),
+            config,
+            &mut pixels,
+        );
+    }
+
+    Ok(pixels)
+}
+
+pub fn part2_with_config(input: &str, config: &CrtConfig) -> Result<String, String> {
+    Ok(draw_screen(input, config)?.join(""))
+}
+
+pub fn part2(input: &str) -> Result<String, String> {
+    part2_with_config(input, &CrtConfig::default())
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<bool>,
+}
+
+impl Frame {
+    // Every pixel is dark until the beam draws it.
+    fn dark(config: &CrtConfig) -> Self {
+        Self {
+            width: config.width,
+            height: config.height,
+            pixels: vec![false; config.number_of_pixels()],
+        }
+    }
+
+    // Lights the pixel under the beam during a cycle if the sprite covers it.
+    fn draw(&mut self, config: &CrtConfig, number_of_cycles: usize, x_register: i64) {
+        let (sprite_begin, sprite_end) = config.sprite(x_register);
+        let position = number_of_cycles - 1;
+
+        if let Some(pixel) = self.pixels.get_mut(position) {
+            let column = (position % config.width) as i64;
+
+            *pixel = column >= sprite_begin && column <= sprite_end;
+        }
+    }
+
+    pub fn encode(&self, format: ImageFormat, pixel_scale: usize) -> Result<Vec<u8>, String> {
+        let grayscale: Vec<u8> = self
+            .pixels
+            .iter()
+            .map(|lit| if *lit { 255 } else { 0 })
+            .collect();
+
+        image::encode_grayscale(
+            format,
+            self.width * pixel_scale,
+            self.height * pixel_scale,
+            &image::scale(self.width, self.height, &grayscale, pixel_scale),
+        )
+    }
+}
+
+// Runs the program on a single framebuffer, handing it to `on_frame` with the
+// number of the cycle after every cycle. Returns the number of cycles.
+fn draw_frames(
+    input: &str,
+    config: &CrtConfig,
+    frame: &mut Frame,
+    mut on_frame: impl FnMut(usize, &Frame) -> Result<(), String>,
+) -> Result<usize, String> {
+    let mut cycles = 0;
+
+    config.validate()?;
+
+    for (number_of_cycles, x_register) in Cpu::new(parse_program(input)) {
+        frame.draw(config, number_of_cycles, x_register);
+        on_frame(number_of_cycles, frame)?;
+        cycles = number_of_cycles;
+    }
+
+    Ok(cycles)
+}
+
+// Frames are produced one at a time, so that they can be written without
+// keeping every one of them.
+pub fn render_frames(
+    input: &str,
+    config: &CrtConfig,
+    on_frame: impl FnMut(usize, &Frame) -> Result<(), String>,
+) -> Result<usize, String> {
+    draw_frames(input, config, &mut Frame::dark(config), on_frame)
+}
+
+pub fn render(input: &str, config: &CrtConfig) -> Result<Frame, String> {
+    let mut frame = Frame::dark(config);
+
+    draw_frames(input, config, &mut frame, |_, _| Ok(()))?;
+    Ok(frame)
+}
+
+static DEBUGGER_HELP: &str = "Commands:
+  step [N], s [N]              Execute N cycles (default: 1)
+  continue, c                  Run until a breakpoint or the end of the program
+  break cycle N, b cycle N     Stop before cycle N
+  break instruction N, b instruction N
+                               Stop before instruction N (0-based) begins
+  delete cycle N               Remove a cycle breakpoint
+  delete instruction N         Remove an instruction breakpoint
+  breakpoints                  List breakpoints
+  watch x, unwatch x           Report every change of the X register
+  print, p                     Show the CPU state
+  crt                          Show the CRT drawn so far
+  help, h                      Show this help
+  quit, q                      Leave the debugger
+";
+
+pub struct Debugger {
+    cpu: Cpu,
+    cycle_breakpoints: HashSet<usize>,
+    instruction_breakpoints: HashSet<usize>,
+    watch_x_register: bool,
+    config: CrtConfig,
+    pixels: Vec<&'static str>,
+    // Number of cycles done when the last breakpoint was reported, so resuming from it makes progress.
+    stopped_at_breakpoint: Option<usize>,
+}
+
+impl Debugger {
+    pub fn new(program: Vec<Instruction>, config: CrtConfig) -> Self {
+        Self {
+            cpu: Cpu::new(program),
+            config,
+            cycle_breakpoints: HashSet::new(),
+            instruction_breakpoints: HashSet::new(),
+            watch_x_register: false,
+            pixels: Vec::new(),
+            stopped_at_breakpoint: None,
+        }
+    }
+
+    pub fn crt(&self) -> String {
+        self.pixels.join("")
+    }
+
+    pub fn state(&self) -> String {
+        match self.cpu.current_instruction() {
+            Some(instruction) => format!(
+                "Cycle {}: X={}, instruction {} ({})",
+                self.cpu.number_of_cycles() + 1,
+                self.cpu.x_register(),
+                self.cpu.instruction_pointer(),
+                instruction
+            ),
+            None => format!(
+                "Program halted after {} cycles: X={}",
+                self.cpu.number_of_cycles(),
+                self.cpu.x_register()
+            ),
+        }
+    }
+
+    fn breakpoint_hit(&self) -> Option<String> {
+        if self.cpu.is_halted() {
+            None
+        } else if self
+            .cycle_breakpoints
+            .contains(&(self.cpu.number_of_cycles() + 1))
+        {
+            Some(format!(
+                "Breakpoint at cycle {}",
+                self.cpu.number_of_cycles() + 1
+            ))
+        } else if self.cpu.is_at_instruction_start()
+            && self
+                .instruction_breakpoints
+                .contains(&self.cpu.instruction_pointer())
+        {
+            Some(format!(
+                "Breakpoint at instruction {}",
+                self.cpu.instruction_pointer()
+            ))
+        } else {
+            None
+        }
+    }
+
+    // Executes one cycle and returns the messages produced by watches.
+    fn step(&mut self) -> Option<Vec<String>> {
+        let x_register_before = self.cpu.x_register();
+        let (number_of_cycles, x_register) = self.cpu.next()?;
+        let mut messages: Vec<String> = Vec::new();
+
+        let (sprite_begin, sprite_end) = self.config.sprite(x_register);
+
+        draw_pixel(
+            &sprite_begin,
+            &sprite_end,
+            &(number_of_cycles as i64 - 1),
+            &self.config,
+            &mut self.pixels,
+        );
+
+        if self.watch_x_register && self.cpu.x_register() != x_register_before {
+            messages.push(format!(
+                "X changed from {} to {} after cycle {}",
+                x_register_before,
+                self.cpu.x_register(),
+                number_of_cycles
+            ));
+        }
+
+        Some(messages)
+    }
+
+    pub fn step_cycles(&mut self, number_of_cycles: usize) -> Vec<String> {
+        let mut messages: Vec<String> = Vec::new();
+
+        for _ in 0..number_of_cycles {
+            if self.stopped_at_breakpoint != Some(self.cpu.number_of_cycles()) {
+                if let Some(message) = self.breakpoint_hit() {
+                    self.stopped_at_breakpoint = Some(self.cpu.number_of_cycles());
+                    messages.push(message);
+                    break;
+                }
             }
-            2 => match instruction[1].parse::<i64>() {
-                Ok(number) => {
-                    for cycle in 0..2 {
-                        draw_pixel(&sprite_begin, &sprite_end, &number_of_cycles, &mut pixels);
-
-                        number_of_cycles += 1;
-
-                        if cycle == 1 {
-                            x_register += number;
-                            sprite_begin = x_register - 1;
-                            sprite_end = x_register + 1;
-                        }
-                    }
+
+            match self.step() {
+                Some(mut watch_messages) => messages.append(&mut watch_messages),
+                None => break,
+            }
+        }
+
+        messages
+    }
+
+    pub fn continue_execution(&mut self) -> Vec<String> {
+        self.step_cycles(usize::MAX)
+    }
+
+    pub fn execute(&mut self, command: &str) -> Result<String, String> {
+        let mut output: Vec<String> = Vec::new();
+
+        match command.split_ascii_whitespace().collect::<Vec<&str>>()[..] {
+            [] => {}
+            ["step" | "s"] => {
+                output.append(&mut self.step_cycles(1));
+                output.push(self.state());
+            }
+            ["step" | "s", number] => match number.parse::<usize>() {
+                Ok(number_of_cycles) => {
+                    output.append(&mut self.step_cycles(number_of_cycles));
+                    output.push(self.state());
                 }
-                Err(_) => continue,
+                Err(_) => return Err(format!("Invalid number of cycles: {}", number)),
             },
-            _ => continue,
+            ["continue" | "c"] => {
+                output.append(&mut self.continue_execution());
+                output.push(self.state());
+            }
+            ["break" | "b", kind, number] => match (kind, number.parse::<usize>()) {
+                ("cycle", Ok(cycle)) => {
+                    self.cycle_breakpoints.insert(cycle);
+                    output.push(format!("Breakpoint set at cycle {}", cycle));
+                }
+                ("instruction", Ok(index)) => {
+                    self.instruction_breakpoints.insert(index);
+                    output.push(format!("Breakpoint set at instruction {}", index));
+                }
+                _ => return Err(format!("Invalid breakpoint: {}", command)),
+            },
+            ["delete", kind, number] => match (kind, number.parse::<usize>()) {
+                ("cycle", Ok(cycle)) if self.cycle_breakpoints.remove(&cycle) => {
+                    output.push(format!("Breakpoint at cycle {} deleted", cycle));
+                }
+                ("instruction", Ok(index)) if self.instruction_breakpoints.remove(&index) => {
+                    output.push(format!("Breakpoint at instruction {} deleted", index));
+                }
+                _ => return Err(format!("No such breakpoint: {}", command)),
+            },
+            ["breakpoints"] => {
+                let mut cycles: Vec<&usize> = self.cycle_breakpoints.iter().collect();
+                let mut instructions: Vec<&usize> = self.instruction_breakpoints.iter().collect();
+
+                cycles.sort();
+                instructions.sort();
+
+                output.push(format!("Cycles: {:?}", cycles));
+                output.push(format!("Instructions: {:?}", instructions));
+            }
+            ["watch", "x" | "X"] => {
+                self.watch_x_register = true;
+                output.push("Watching X register".to_string());
+            }
+            ["unwatch", "x" | "X"] => {
+                self.watch_x_register = false;
+                output.push("Not watching X register".to_string());
+            }
+            ["print" | "p"] => output.push(self.state()),
+            ["crt"] => output.push(self.crt()),
+            ["help" | "h"] => output.push(DEBUGGER_HELP.to_string()),
+            _ => return Err(format!("Unknown command: {}", command)),
         }
+
+        Ok(output.join("\n"))
     }
 
-    Ok(pixels.join(""))
+    pub fn run(&mut self, input: impl BufRead, output: &mut impl Write) -> Result<(), String> {
+        let mut lines = input.lines();
+
+        loop {
+            write!(output, "(day10) ").map_err(|err| err.to_string())?;
+            output.flush().map_err(|err| err.to_string())?;
+
+            let command = match lines.next() {
+                Some(Ok(line)) => line,
+                Some(Err(err)) => return Err(err.to_string()),
+                None => break,
+            };
+
+            let result = match command.trim() {
+                "quit" | "q" => break,
+                other => self.execute(other),
+            };
+
+            match result {
+                Ok(message) if message.is_empty() => {}
+                Ok(message) => writeln!(output, "{}", message).map_err(|err| err.to_string())?,
+                Err(err) => writeln!(output, "{}", err).map_err(|err| err.to_string())?,
+            }
+        }
+
+        writeln!(output).map_err(|err| err.to_string())
+    }
+}
+
+pub fn debug(
+    input: &str,
+    config: &CrtConfig,
+    commands: impl BufRead,
+    output: &mut impl Write,
+) -> Result<(), String> {
+    config.validate()?;
+
+    let mut debugger = Debugger::new(parse_program(input), config.clone());
+
+    writeln!(output, "{}", debugger.state()).map_err(|err| err.to_string())?;
+    debugger.run(commands, output)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day10::{part1, part2};
+    use crate::day10::{
+        debug, parse_program, part1, part1_with_config, part2, part2_with_config, render,
+        render_frames, Cpu, CrtConfig, Debugger, Frame, Instruction,
+    };
+    use crate::image::ImageFormat;
 
     static TEST_INPUT: &str = "addx 15
 addx -11
@@ -454,6 +941,11 @@ addx -11
 noop
 noop
 noop
+";
+
+    static TEST_INPUT_SMALL: &str = "noop
+addx 3
+addx -5
 ";
 
     static SCREEN_OUTPUT: &str = "##..##..##..##..##..##..##..##..##..##..
@@ -489,4 +981,321 @@ noop
             Ok(SCREEN_OUTPUT.to_string())
         );
     }
+
+    #[test]
+    fn test_parse_program() {
+        assert_eq!(
+            parse_program(&format!("{}{}", TEST_INPUT_SMALL, "AA BB\n")),
+            vec![
+                Instruction::Noop,
+                Instruction::Addx(3),
+                Instruction::Addx(-5)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cpu() {
+        assert_eq!(
+            Cpu::new(parse_program(TEST_INPUT_SMALL)).collect::<Vec<(usize, i64)>>(),
+            vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]
+        );
+    }
+
+    #[test]
+    fn test_debugger_step() {
+        let mut debugger = Debugger::new(parse_program(TEST_INPUT_SMALL), CrtConfig::default());
+
+        assert_eq!(
+            debugger.execute("print"),
+            Ok("Cycle 1: X=1, instruction 0 (noop)".to_string())
+        );
+        assert_eq!(
+            debugger.execute("step 3"),
+            Ok("Cycle 4: X=4, instruction 2 (addx -5)".to_string())
+        );
+        assert_eq!(
+            debugger.execute("s"),
+            Ok("Cycle 5: X=4, instruction 2 (addx -5)".to_string())
+        );
+        assert_eq!(
+            debugger.execute("step 10"),
+            Ok("Program halted after 5 cycles: X=-1".to_string())
+        );
+        assert_eq!(
+            debugger.execute("step abc"),
+            Err("Invalid number of cycles: abc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_debugger_breakpoints() {
+        let mut debugger = Debugger::new(parse_program(TEST_INPUT), CrtConfig::default());
+
+        assert_eq!(
+            debugger.execute("break cycle 20"),
+            Ok("Breakpoint set at cycle 20".to_string())
+        );
+        assert_eq!(
+            debugger.execute("b instruction 2"),
+            Ok("Breakpoint set at instruction 2".to_string())
+        );
+        assert_eq!(
+            debugger.execute("continue"),
+            Ok("Breakpoint at instruction 2\nCycle 5: X=5, instruction 2 (addx 6)".to_string())
+        );
+        assert_eq!(
+            debugger.execute("c"),
+            Ok("Breakpoint at cycle 20\nCycle 20: X=21, instruction 10 (addx -1)".to_string())
+        );
+        assert_eq!(
+            debugger.execute("delete cycle 20"),
+            Ok("Breakpoint at cycle 20 deleted".to_string())
+        );
+        assert_eq!(
+            debugger.execute("delete cycle 20"),
+            Err("No such breakpoint: delete cycle 20".to_string())
+        );
+        assert_eq!(
+            debugger.execute("break line 20"),
+            Err("Invalid breakpoint: break line 20".to_string())
+        );
+        assert_eq!(
+            debugger.execute("breakpoints"),
+            Ok("Cycles: []\nInstructions: [2]".to_string())
+        );
+        assert_eq!(
+            debugger.execute("c"),
+            Ok("Program halted after 240 cycles: X=17".to_string())
+        );
+        assert_eq!(debugger.crt(), SCREEN_OUTPUT.to_string());
+    }
+
+    #[test]
+    fn test_debugger_breakpoint_on_first_cycle() {
+        let mut debugger = Debugger::new(parse_program(TEST_INPUT), CrtConfig::default());
+
+        assert_eq!(
+            debugger.execute("break cycle 1"),
+            Ok("Breakpoint set at cycle 1".to_string())
+        );
+        assert_eq!(
+            debugger.execute("continue"),
+            Ok("Breakpoint at cycle 1\nCycle 1: X=1, instruction 0 (addx 15)".to_string())
+        );
+        assert_eq!(
+            debugger.execute("c"),
+            Ok("Program halted after 240 cycles: X=17".to_string())
+        );
+    }
+
+    #[test]
+    fn test_debugger_watch() {
+        let mut debugger = Debugger::new(parse_program(TEST_INPUT_SMALL), CrtConfig::default());
+
+        assert_eq!(
+            debugger.execute("watch x"),
+            Ok("Watching X register".to_string())
+        );
+        assert_eq!(
+            debugger.execute("c"),
+            Ok("X changed from 1 to 4 after cycle 3
+X changed from 4 to -1 after cycle 5
+Program halted after 5 cycles: X=-1"
+                .to_string())
+        );
+        assert_eq!(
+            debugger.execute("unwatch x"),
+            Ok("Not watching X register".to_string())
+        );
+        assert_eq!(
+            debugger.execute("jump"),
+            Err("Unknown command: jump".to_string())
+        );
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut output: Vec<u8> = Vec::new();
+
+        assert_eq!(
+            debug(
+                TEST_INPUT,
+                &CrtConfig::default(),
+                "step 40\ncrt\nquit\nstep\n".as_bytes(),
+                &mut output
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Cycle 1: X=1, instruction 0 (addx 15)
+(day10) Cycle 41: X=1, instruction 20 (addx 1)
+(day10) ##..##..##..##..##..##..##..##..##..##..
+
+(day10) \n"
+        );
+    }
+
+    #[test]
+    fn test_crt_config_validate() {
+        assert_eq!(CrtConfig::default().validate(), Ok(()));
+        assert_eq!(
+            CrtConfig {
+                width: 0,
+                ..CrtConfig::default()
+            }
+            .validate(),
+            Err("CRT width must be at least 1".to_string())
+        );
+        assert_eq!(
+            part1_with_config(
+                TEST_INPUT,
+                &CrtConfig {
+                    sample_interval: 0,
+                    ..CrtConfig::default()
+                }
+            ),
+            Err("Sample interval must be at least 1".to_string())
+        );
+        assert_eq!(
+            part2_with_config(
+                TEST_INPUT,
+                &CrtConfig {
+                    sprite_width: 0,
+                    ..CrtConfig::default()
+                }
+            ),
+            Err("Sprite width must be at least 1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_crt_config_sprite() {
+        let config = CrtConfig {
+            sprite_width: 4,
+            ..CrtConfig::default()
+        };
+
+        assert_eq!(CrtConfig::default().sprite(5), (4, 6));
+        assert_eq!(config.sprite(5), (4, 7));
+    }
+
+    #[test]
+    fn test_part1_with_config() {
+        let config = CrtConfig {
+            sample_start: 10,
+            sample_interval: 100,
+            ..CrtConfig::default()
+        };
+
+        // Cycles 10 (X=8), 110 (X=13) and 210 (X=-1).
+        assert_eq!(
+            part1_with_config(TEST_INPUT, &config),
+            Ok((10 * 8 + 110 * 13 - 210).to_string())
+        );
+    }
+
+    #[test]
+    fn test_part2_with_config() {
+        let config = CrtConfig {
+            width: 80,
+            height: 2,
+            sprite_width: 1,
+            ..CrtConfig::default()
+        };
+
+        assert_eq!(
+            part2_with_config(TEST_INPUT_SMALL, &config),
+            Ok(".#..#".to_string())
+        );
+        assert_eq!(
+            part2_with_config(
+                TEST_INPUT,
+                &CrtConfig {
+                    height: 1,
+                    ..CrtConfig::default()
+                }
+            ),
+            Ok("##..##..##..##..##..##..##..##..##..##..\n".to_string())
+        );
+        assert_eq!(
+            part2_with_config(TEST_INPUT, &config)
+                .unwrap()
+                .lines()
+                .map(|line| line.len())
+                .collect::<Vec<usize>>(),
+            vec![80, 80]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let frame = render(TEST_INPUT, &CrtConfig::default()).unwrap();
+
+        assert_eq!((frame.width, frame.height), (40, 6));
+        assert_eq!(
+            frame
+                .pixels
+                .chunks(40)
+                .map(|row| row
+                    .iter()
+                    .map(|lit| if *lit { '#' } else { '.' })
+                    .collect::<String>()
+                    + "\n")
+                .collect::<String>(),
+            SCREEN_OUTPUT
+        );
+    }
+
+    #[test]
+    fn test_render_empty_program() {
+        assert_eq!(
+            render("", &CrtConfig::default()),
+            Ok(Frame {
+                width: 40,
+                height: 6,
+                pixels: vec![false; 240]
+            })
+        );
+    }
+
+    #[test]
+    fn test_render_frames() {
+        let mut frames: Vec<Frame> = vec![];
+
+        assert_eq!(
+            render_frames(TEST_INPUT_SMALL, &CrtConfig::default(), |number, frame| {
+                assert_eq!(number, frames.len() + 1);
+                frames.push(frame.clone());
+                Ok(())
+            }),
+            Ok(5)
+        );
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[1].pixels[..5], [true, true, false, false, false]);
+        assert_eq!(frames[4].pixels[..5], [true, true, true, true, true]);
+    }
+
+    #[test]
+    fn test_frame_encode() {
+        let frame = Frame {
+            width: 2,
+            height: 1,
+            pixels: vec![true, false],
+        };
+
+        assert_eq!(
+            frame.encode(ImageFormat::Pgm, 2),
+            Ok([
+                b"P5\n4 2\n255\n".to_vec(),
+                vec![255, 255, 0, 0, 255, 255, 0, 0]
+            ]
+            .concat())
+        );
+        assert_eq!(
+            frame.encode(ImageFormat::Pbm, 1),
+            Ok([b"P4\n2 1\n".to_vec(), vec![0b0100_0000]].concat())
+        );
+    }
 }
@@ -44,93 +44,275 @@ Now that you're correctly decrypting the ultra top secret strategy guide, you wo
 Following the Elf's instructions for the second column, what would your total score be if everything goes exactly according to your strategy guide?
 */
 
-enum OurShape {
-    Rock = 1,
-    Paper = 2,
-    Scissors = 3,
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
 }
 
-enum Outcome {
-    Loss = 0,
-    Draw = 3,
-    Win = 6,
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shape {
+    pub name: String,
+    pub score: i64,
 }
 
-pub fn part1(input: &str) -> Result<String, String> {
-    let mut total_score: i64 = 0;
-
-    for line in input.lines() {
-        total_score += match line {
-            "A X" => OurShape::Rock as i64 + Outcome::Draw as i64,
-            "A Y" => OurShape::Paper as i64 + Outcome::Win as i64,
-            "A Z" => OurShape::Scissors as i64 + Outcome::Loss as i64,
-            "B X" => OurShape::Rock as i64 + Outcome::Loss as i64,
-            "B Y" => OurShape::Paper as i64 + Outcome::Draw as i64,
-            "B Z" => OurShape::Scissors as i64 + Outcome::Win as i64,
-            "C X" => OurShape::Rock as i64 + Outcome::Win as i64,
-            "C Y" => OurShape::Paper as i64 + Outcome::Loss as i64,
-            "C Z" => OurShape::Scissors as i64 + Outcome::Draw as i64,
-            _ => {
-                println!(
-                    "Unsupported line: '{}'. Using a score of 0 for this line.",
-                    line
-                );
-                0
-            }
+// There are at most as many shapes as letters, so that every shape has a code.
+pub const MAX_SHAPES: usize = 26;
+
+// Shapes of a game, and which shape wins against which. Shapes not winning
+// against each other either way end in a draw.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rules {
+    shapes: Vec<Shape>,
+    // Pairs of shape indices, the first shape winning against the second.
+    wins: Vec<(usize, usize)>,
+}
+
+impl Rules {
+    pub fn new(shapes: &[(&str, i64)], wins: &[(&str, &str)]) -> Result<Self, String> {
+        let index_of = |name: &str| {
+            shapes
+                .iter()
+                .position(|(shape, _)| *shape == name)
+                .ok_or_else(|| format!("Unknown shape: {}", name))
         };
+        let mut shape_wins: Vec<(usize, usize)> = vec![];
+
+        if shapes.len() > MAX_SHAPES {
+            return Err(format!(
+                "Too many shapes: {}, there can be at most {}",
+                shapes.len(),
+                MAX_SHAPES
+            ));
+        }
+
+        for (index, (name, _)) in shapes.iter().enumerate() {
+            if index_of(name)? != index {
+                return Err(format!("Duplicate shape: {}", name));
+            }
+        }
+
+        for (winner, loser) in wins {
+            let win = (index_of(winner)?, index_of(loser)?);
+
+            if win.0 == win.1 {
+                return Err(format!("Shape cannot win against itself: {}", winner));
+            } else if shape_wins.contains(&(win.1, win.0)) {
+                return Err(format!(
+                    "Contradictory wins between {} and {}",
+                    loser, winner
+                ));
+            }
+
+            shape_wins.push(win);
+        }
+
+        Ok(Rules {
+            shapes: shapes
+                .iter()
+                .map(|(name, score)| Shape {
+                    name: name.to_string(),
+                    score: *score,
+                })
+                .collect(),
+            wins: shape_wins,
+        })
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        Rules::new(
+            &[("Rock", 1), ("Paper", 2), ("Scissors", 3)],
+            &[
+                ("Rock", "Scissors"),
+                ("Paper", "Rock"),
+                ("Scissors", "Paper"),
+            ],
+        )
+        .unwrap()
     }
 
-    Ok(total_score.to_string())
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Rules::new(
+            &[
+                ("Rock", 1),
+                ("Paper", 2),
+                ("Scissors", 3),
+                ("Lizard", 4),
+                ("Spock", 5),
+            ],
+            &[
+                ("Rock", "Scissors"),
+                ("Rock", "Lizard"),
+                ("Paper", "Rock"),
+                ("Paper", "Spock"),
+                ("Scissors", "Paper"),
+                ("Scissors", "Lizard"),
+                ("Lizard", "Paper"),
+                ("Lizard", "Spock"),
+                ("Spock", "Rock"),
+                ("Spock", "Scissors"),
+            ],
+        )
+        .unwrap()
+    }
+
+    pub fn shapes(&self) -> &[Shape] {
+        &self.shapes
+    }
+
+    pub fn outcome(&self, our_shape: usize, their_shape: usize) -> Outcome {
+        if self.wins.contains(&(our_shape, their_shape)) {
+            Outcome::Win
+        } else if self.wins.contains(&(their_shape, our_shape)) {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    // First of our shapes, in the order of the rules, giving the outcome against theirs.
+    pub fn shape_for_outcome(&self, their_shape: usize, outcome: Outcome) -> Option<usize> {
+        (0..self.shapes.len()).find(|our_shape| self.outcome(*our_shape, their_shape) == outcome)
+    }
 }
 
-/*
-impl From<Move> for u32 {
-    /// Converts the given `Move` into its score value.
-    ///
-    fn from(value: Move) -> Self {
-        match value {
-            Move::Rock => 1,
-            Move::Paper => 2,
-            Move::Scissor => 3,
+impl FromStr for Rules {
+    type Err = String;
+
+    fn from_str(rules: &str) -> Result<Self, Self::Err> {
+        match rules {
+            "rock-paper-scissors" => Ok(Rules::rock_paper_scissors()),
+            "rock-paper-scissors-lizard-spock" => Ok(Rules::rock_paper_scissors_lizard_spock()),
+            other => Err(format!("Unsupported rules: {}", other)),
         }
     }
 }
 
-I could have used this too. I would have first needed to split the string
-and convert each letter to an enum value, then use the above code to convert
-back the enum value to a numeric value to be used to calculate the score.
-*/
+// Meaning of the second column of the strategy guide.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Decoding {
+    Shape,
+    Outcome,
+}
 
-pub fn part2(input: &str) -> Result<String, String> {
-    let mut total_score: i64 = 0;
-
-    for line in input.lines() {
-        total_score += match line {
-            "A X" => OurShape::Scissors as i64 + Outcome::Loss as i64,
-            "A Y" => OurShape::Rock as i64 + Outcome::Draw as i64,
-            "A Z" => OurShape::Paper as i64 + Outcome::Win as i64,
-            "B X" => OurShape::Rock as i64 + Outcome::Loss as i64,
-            "B Y" => OurShape::Paper as i64 + Outcome::Draw as i64,
-            "B Z" => OurShape::Scissors as i64 + Outcome::Win as i64,
-            "C X" => OurShape::Paper as i64 + Outcome::Loss as i64,
-            "C Y" => OurShape::Scissors as i64 + Outcome::Draw as i64,
-            "C Z" => OurShape::Rock as i64 + Outcome::Win as i64,
-            _ => {
-                println!(
-                    "Unsupported line: '{}'. Using a score of 0 for this line.",
-                    line
-                );
-                0
+pub type Scoring = fn(&Shape, Outcome) -> i64;
+
+pub fn puzzle_scoring(shape: &Shape, outcome: Outcome) -> i64 {
+    shape.score
+        + match outcome {
+            Outcome::Loss => 0,
+            Outcome::Draw => 3,
+            Outcome::Win => 6,
+        }
+}
+
+pub struct Game {
+    pub rules: Rules,
+    pub decoding: Decoding,
+    pub scoring: Scoring,
+    // Codes of their shapes, and of our shapes or of the outcomes from loss to win.
+    pub their_codes: Vec<String>,
+    pub our_codes: Vec<String>,
+}
+
+impl Game {
+    // Their shapes are coded from A, and our shapes are coded up to Z, like X, Y
+    // and Z for three shapes. Outcomes are coded X, Y and Z.
+    // When decoding outcomes, every outcome must be reachable against every shape.
+    pub fn new(rules: Rules, decoding: Decoding) -> Result<Self, String> {
+        let number_of_shapes = rules.shapes().len();
+        let codes = |skip: usize, take: usize| -> Vec<String> {
+            (b'A'..=b'Z')
+                .skip(skip)
+                .take(take)
+                .map(|code| (code as char).to_string())
+                .collect()
+        };
+        let our_codes = match decoding {
+            Decoding::Shape => codes(MAX_SHAPES - number_of_shapes, number_of_shapes),
+            Decoding::Outcome => codes(23, 3),
+        };
+
+        if decoding == Decoding::Outcome {
+            for (their_shape, shape) in rules.shapes().iter().enumerate() {
+                for (outcome, description) in [(Outcome::Win, "winning"), (Outcome::Loss, "losing")]
+                {
+                    if rules.shape_for_outcome(their_shape, outcome).is_none() {
+                        return Err(format!(
+                            "No shape {} against {} in the rules",
+                            description, shape.name
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(Game {
+            rules,
+            decoding,
+            scoring: puzzle_scoring,
+            their_codes: codes(0, number_of_shapes),
+            our_codes,
+        })
+    }
+
+    pub fn round_score(&self, line: &str) -> Option<i64> {
+        let (their_code, our_code) = line.split_once(' ')?;
+        let their_shape = self
+            .their_codes
+            .iter()
+            .position(|code| code == their_code)?;
+        let our_code = self.our_codes.iter().position(|code| code == our_code)?;
+        let (our_shape, outcome) = match self.decoding {
+            Decoding::Shape => (our_code, self.rules.outcome(our_code, their_shape)),
+            Decoding::Outcome => {
+                let outcome = [Outcome::Loss, Outcome::Draw, Outcome::Win][our_code];
+
+                (self.rules.shape_for_outcome(their_shape, outcome)?, outcome)
             }
         };
+
+        Some((self.scoring)(&self.rules.shapes()[our_shape], outcome))
     }
 
-    Ok(total_score.to_string())
+    pub fn total_score(&self, input: &str) -> i64 {
+        let mut total_score: i64 = 0;
+
+        for line in input.lines() {
+            total_score += match self.round_score(line) {
+                Some(score) => score,
+                None => {
+                    println!(
+                        "Unsupported line: '{}'. Using a score of 0 for this line.",
+                        line
+                    );
+                    0
+                }
+            };
+        }
+
+        total_score
+    }
+}
+
+pub fn part1(input: &str) -> Result<String, String> {
+    let game = Game::new(Rules::rock_paper_scissors(), Decoding::Shape)?;
+
+    Ok(game.total_score(input).to_string())
+}
+
+pub fn part2(input: &str) -> Result<String, String> {
+    let game = Game::new(Rules::rock_paper_scissors(), Decoding::Outcome)?;
+
+    Ok(game.total_score(input).to_string())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day2::{part1, part2};
+    use crate::day2::{part1, part2, Decoding, Game, Outcome, Rules, Shape, MAX_SHAPES};
 
     static TEST_INPUT: &str = "A Y
 B X
@@ -157,4 +339,85 @@ K P";
         assert_eq!(part2(TEST_INPUT_ALL_BAD), Ok(0.to_string()));
         assert_eq!(part1(TEST_INPUT_PARTIALLY_BAD), Ok(8.to_string()));
     }
+
+    #[test]
+    fn test_rules() {
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+
+        assert_eq!(rules.outcome(3, 4), Outcome::Win);
+        assert_eq!(rules.outcome(4, 3), Outcome::Loss);
+        assert_eq!(rules.outcome(2, 2), Outcome::Draw);
+        assert_eq!(rules.shape_for_outcome(0, Outcome::Win), Some(1));
+        assert_eq!(
+            Rules::new(&[("Rock", 1)], &[("Rock", "Paper")]),
+            Err("Unknown shape: Paper".to_string())
+        );
+        assert_eq!(
+            Rules::new(&[("Rock", 1), ("Rock", 2)], &[]),
+            Err("Duplicate shape: Rock".to_string())
+        );
+        assert_eq!(
+            Rules::new(&[("Rock", 1)], &[("Rock", "Rock")]),
+            Err("Shape cannot win against itself: Rock".to_string())
+        );
+        assert_eq!(
+            Rules::new(
+                &[("Rock", 1), ("Paper", 2)],
+                &[("Paper", "Rock"), ("Rock", "Paper")]
+            ),
+            Err("Contradictory wins between Paper and Rock".to_string())
+        );
+
+        let names: Vec<String> = (0..=MAX_SHAPES).map(|index| index.to_string()).collect();
+        let shapes: Vec<(&str, i64)> = names.iter().map(|name| (name.as_str(), 1)).collect();
+
+        assert_eq!(
+            Rules::new(&shapes, &[]),
+            Err("Too many shapes: 27, there can be at most 26".to_string())
+        );
+        assert!(Rules::new(&shapes[..MAX_SHAPES], &[]).is_ok());
+        assert_eq!(
+            "rock-paper-scissors".parse::<Rules>(),
+            Ok(Rules::rock_paper_scissors())
+        );
+    }
+
+    #[test]
+    fn test_game_with_lizard_and_spock() {
+        let game = Game::new(Rules::rock_paper_scissors_lizard_spock(), Decoding::Shape).unwrap();
+
+        assert_eq!(game.our_codes, vec!["V", "W", "X", "Y", "Z"]);
+        assert_eq!(game.round_score("A W"), Some(2 + 6));
+        assert_eq!(game.round_score("E Y"), Some(4 + 6));
+        assert_eq!(game.round_score("E V"), Some(1));
+        assert_eq!(game.round_score("F V"), None);
+
+        let game = Game::new(Rules::rock_paper_scissors_lizard_spock(), Decoding::Outcome).unwrap();
+
+        assert_eq!(game.round_score("E Z"), Some(2 + 6));
+        assert_eq!(game.round_score("E Y"), Some(5 + 3));
+    }
+
+    #[test]
+    fn test_game_with_unreachable_outcome() {
+        let rules = Rules::new(&[("Rock", 1), ("Paper", 2)], &[("Paper", "Rock")]).unwrap();
+        let game = Game::new(rules.clone(), Decoding::Shape).unwrap();
+
+        assert_eq!(game.their_codes, vec!["A", "B"]);
+        assert_eq!(game.our_codes, vec!["Y", "Z"]);
+        assert_eq!(game.round_score("A Z"), Some(2 + 6));
+        assert!(Game::new(rules, Decoding::Outcome)
+            .is_err_and(|err| err == "No shape losing against Rock in the rules"));
+    }
+
+    #[test]
+    fn test_game_with_custom_scoring() {
+        let mut game = Game::new(Rules::rock_paper_scissors(), Decoding::Shape).unwrap();
+
+        game.scoring = |shape: &Shape, outcome: Outcome| match outcome {
+            Outcome::Win => shape.score * 10,
+            _ => 0,
+        };
+        assert_eq!(game.total_score(TEST_INPUT), 20);
+    }
 }
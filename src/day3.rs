@@ -63,78 +63,326 @@ Priorities for these items must still be found to organize the sticker attachmen
 Find the item type that corresponds to the badges of each three-Elf group. What is the sum of the priorities of those item types?
 */
 
-use std::collections::HashSet;
+use std::fmt;
+use std::ops::BitAnd;
 
-fn get_item_type_priority(item_type: &char) -> u8 {
-    if item_type.is_lowercase() {
-        *item_type as u8 - b'a' + 1
-    } else {
-        *item_type as u8 - b'A' + 1 + 26
+pub const GROUP_SIZE: usize = 3;
+
+// Item types are letters, with priorities from 1 to 52.
+fn get_item_type_priority(item_type: u8) -> Option<u8> {
+    match item_type {
+        b'a'..=b'z' => Some(item_type - b'a' + 1),
+        b'A'..=b'Z' => Some(item_type - b'A' + 27),
+        _ => None,
     }
 }
 
-pub fn part1(input: &str) -> Result<String, String> {
-    let mut sum_of_priorities: i64 = 0;
+fn get_item_type(priority: u8) -> char {
+    match priority {
+        1..=26 => (b'a' + priority - 1) as char,
+        _ => (b'A' + priority - 27) as char,
+    }
+}
+
+// Item types in a rucksack, as a bitset where the bit of an item type is its priority.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rucksack(pub u64);
+
+impl Rucksack {
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn number_of_item_types(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    // Priorities of the item types, from the lowest.
+    pub fn priorities(&self) -> impl Iterator<Item = u8> {
+        let mut item_types = self.0;
+
+        std::iter::from_fn(move || match item_types {
+            0 => None,
+            _ => {
+                let priority = item_types.trailing_zeros() as u8;
 
-    for line in input.lines() {
-        let mut second_half = String::from(line);
-        let first_half = second_half.split_off(line.len() / 2);
+                item_types &= item_types - 1;
+                Some(priority)
+            }
+        })
+    }
+
+    // Item types with their priority, like "a (1), Z (52)".
+    pub fn describe(&self) -> String {
+        self.priorities()
+            .map(|priority| format!("{} ({})", get_item_type(priority), priority))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    // Priority of the item type, if the rucksack holds a single one.
+    pub fn priority(&self) -> Option<u8> {
+        match self.number_of_item_types() {
+            1 => Some(self.0.trailing_zeros() as u8),
+            _ => None,
+        }
+    }
+}
+
+impl BitAnd for Rucksack {
+    type Output = Rucksack;
+
+    fn bitand(self, other: Rucksack) -> Rucksack {
+        Rucksack(self.0 & other.0)
+    }
+}
 
-        let items_in_first_compartment: HashSet<char> = first_half.chars().collect();
-        let items_in_second_compartment: HashSet<char> = second_half.chars().collect();
+// Strict validation rejects any invalid rucksack or group, while lenient
+// validation reports them as warnings and carries on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Validation {
+    Strict,
+    Lenient,
+}
 
-        let shared_item_type: Vec<_> = items_in_first_compartment
-            .intersection(&items_in_second_compartment)
-            .collect();
+// Sum of the priorities, with the warnings raised by lenient validation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Priorities {
+    pub sum: u64,
+    pub warnings: Vec<String>,
+}
 
-        sum_of_priorities += get_item_type_priority(shared_item_type[0]) as i64;
+impl Priorities {
+    fn report(&mut self, validation: Validation, problem: String) -> Result<(), String> {
+        match validation {
+            Validation::Strict => Err(problem),
+            Validation::Lenient => {
+                self.warnings.push(problem);
+                Ok(())
+            }
+        }
     }
 
-    Ok(sum_of_priorities.to_string())
+    // Rucksack of the items of a line, leaving out the items which are not letters.
+    fn parse_rucksack(
+        &mut self,
+        validation: Validation,
+        line_number: usize,
+        items: &str,
+    ) -> Result<Rucksack, String> {
+        let mut rucksack = Rucksack::default();
+
+        for (index, item_type) in items.bytes().enumerate() {
+            match get_item_type_priority(item_type) {
+                Some(priority) => rucksack.0 |= 1 << priority,
+                // Only the first byte of a character other than ASCII is reported.
+                None if (0x80..0xc0).contains(&item_type) => (),
+                None => self.report(
+                    validation,
+                    format!(
+                        "Unknown item type '{}' at line {}",
+                        items[index..].chars().next().unwrap_or_default(),
+                        line_number
+                    ),
+                )?,
+            }
+        }
+
+        Ok(rucksack)
+    }
 }
 
-fn get_intersection(group: &mut Vec<HashSet<char>>) -> Option<char> {
-    if let Some(mut intersection) = group.pop() {
-        for rucksack in group {
-            intersection = intersection
-                .intersection(rucksack)
-                .copied()
-                .collect::<HashSet<char>>();
+pub fn compartment_priorities(input: &str, validation: Validation) -> Result<Priorities, String> {
+    let mut priorities = Priorities::default();
+
+    for (index, line) in input.lines().enumerate() {
+        // Items are characters, which only need counting in lines which are not ASCII.
+        let (number_of_items, middle) = match line.is_ascii() {
+            true => (line.len(), line.len() / 2),
+            false => {
+                let number_of_items = line.chars().count();
+
+                (
+                    number_of_items,
+                    line.char_indices()
+                        .nth(number_of_items / 2)
+                        .map_or(line.len(), |(middle, _)| middle),
+                )
+            }
+        };
+        let (first_compartment, second_compartment) = line.split_at(middle);
+
+        if number_of_items % 2 == 1 {
+            priorities.report(
+                validation,
+                format!("Odd number of items at line {}", index + 1),
+            )?;
         }
 
-        if intersection.len() == 1 {
-            Some(intersection.into_iter().collect::<Vec<char>>()[0])
-        } else {
-            None
+        let shared_item_types =
+            priorities.parse_rucksack(validation, index + 1, first_compartment)?
+                & priorities.parse_rucksack(validation, index + 1, second_compartment)?;
+
+        match shared_item_types.priorities().next() {
+            Some(priority) => priorities.sum += priority as u64,
+            None => priorities.report(
+                validation,
+                format!(
+                    "No item type shared by both compartments at line {}",
+                    index + 1
+                ),
+            )?,
         }
-    } else {
-        None
     }
+
+    Ok(priorities)
 }
 
-pub fn part2(input: &str) -> Result<String, String> {
-    let mut sum_of_priorities: i64 = 0;
-    let mut list_of_group_item_types: Vec<HashSet<char>> = Vec::new();
+pub fn part1(input: &str) -> Result<String, String> {
+    Ok(compartment_priorities(input, Validation::Strict)?
+        .sum
+        .to_string())
+}
 
-    for (position, line) in input.lines().enumerate() {
-        list_of_group_item_types.push(line.chars().collect());
+// Item types shared by every rucksack of the group.
+fn get_intersection(group: &[Rucksack]) -> Rucksack {
+    match group.split_first() {
+        Some((first, others)) => others
+            .iter()
+            .fold(*first, |intersection, rucksack| intersection & *rucksack),
+        None => Rucksack::default(),
+    }
+}
 
-        if ((position + 1) % 3) == 0 {
-            if let Some(intersection) = get_intersection(&mut list_of_group_item_types) {
-                sum_of_priorities += get_item_type_priority(&intersection) as i64;
-            }
+// Item types shared by a group of rucksacks from consecutive lines.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    pub first_line_number: usize,
+    pub last_line_number: usize,
+    pub shared_item_types: Rucksack,
+}
 
-            list_of_group_item_types.clear()
+impl fmt::Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.shared_item_types.is_empty() {
+            true => write!(
+                f,
+                "Lines {}-{}: none",
+                self.first_line_number, self.last_line_number
+            ),
+            false => write!(
+                f,
+                "Lines {}-{}: {}",
+                self.first_line_number,
+                self.last_line_number,
+                self.shared_item_types.describe()
+            ),
         }
     }
+}
+
+fn parse_groups(
+    input: &str,
+    group_size: usize,
+    validation: Validation,
+    priorities: &mut Priorities,
+) -> Result<Vec<Group>, String> {
+    let mut rucksacks: Vec<Rucksack> = vec![];
+
+    if group_size == 0 {
+        return Err("Group size must be at least 1".to_string());
+    }
 
-    Ok(sum_of_priorities.to_string())
+    for (index, line) in input.lines().enumerate() {
+        rucksacks.push(priorities.parse_rucksack(validation, index + 1, line)?);
+    }
+
+    let mut groups: Vec<Group> = vec![];
+
+    for (index, group) in rucksacks.chunks(group_size).enumerate() {
+        let first_line_number = index * group_size + 1;
+        let last_line_number = first_line_number + group.len() - 1;
+
+        if group.len() < group_size {
+            priorities.report(
+                validation,
+                format!(
+                    "Incomplete group of {} rucksack(s) at lines {}-{}",
+                    group.len(),
+                    first_line_number,
+                    last_line_number
+                ),
+            )?;
+            continue;
+        }
+
+        groups.push(Group {
+            first_line_number,
+            last_line_number,
+            shared_item_types: get_intersection(group),
+        });
+    }
+
+    Ok(groups)
+}
+
+// Every complete group of rucksacks, with all the item types it shares, and the
+// warnings about the rucksacks and groups left out.
+pub fn shared_item_types(
+    input: &str,
+    group_size: usize,
+    validation: Validation,
+) -> Result<(Vec<Group>, Vec<String>), String> {
+    let mut priorities = Priorities::default();
+    let groups = parse_groups(input, group_size, validation, &mut priorities)?;
+
+    Ok((groups, priorities.warnings))
+}
+
+pub fn group_priorities(
+    input: &str,
+    group_size: usize,
+    validation: Validation,
+) -> Result<Priorities, String> {
+    let mut priorities = Priorities::default();
+
+    for group in parse_groups(input, group_size, validation, &mut priorities)? {
+        match group.shared_item_types.priority() {
+            Some(priority) => priorities.sum += priority as u64,
+            None if group.shared_item_types.is_empty() => priorities.report(
+                validation,
+                format!(
+                    "No item type shared by the group at lines {}-{}",
+                    group.first_line_number, group.last_line_number
+                ),
+            )?,
+            None => priorities.report(
+                validation,
+                format!(
+                    "Several item types shared by the group at lines {}-{}: {}",
+                    group.first_line_number,
+                    group.last_line_number,
+                    group.shared_item_types.describe()
+                ),
+            )?,
+        }
+    }
+
+    Ok(priorities)
+}
+
+pub fn part2(input: &str) -> Result<String, String> {
+    Ok(group_priorities(input, GROUP_SIZE, Validation::Strict)?
+        .sum
+        .to_string())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day3::{get_intersection, part1, part2};
-    use std::collections::HashSet;
+    use crate::day3::{
+        compartment_priorities, get_intersection, get_item_type_priority, group_priorities, part1,
+        part2, shared_item_types, Priorities, Rucksack, Validation,
+    };
 
     static TEST_INPUT: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
 jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
@@ -143,28 +391,62 @@ wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
 ttgJtRGJQctTZtZT
 CrZsJsPPZsGzwwsLwLmpwMDw";
 
+    #[test]
+    fn test_get_item_type_priority() {
+        assert_eq!(get_item_type_priority(b'a'), Some(1));
+        assert_eq!(get_item_type_priority(b'z'), Some(26));
+        assert_eq!(get_item_type_priority(b'A'), Some(27));
+        assert_eq!(get_item_type_priority(b'Z'), Some(52));
+        assert_eq!(get_item_type_priority(b'1'), None);
+    }
+
+    fn parse_items(items: &str) -> Rucksack {
+        Priorities::default()
+            .parse_rucksack(Validation::Strict, 1, items)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rucksack() {
+        let rucksack = parse_items("vJrwpWtwJgWr");
+        let shared_item_types = rucksack & parse_items("hcsFMMfFFhFp");
+
+        assert_eq!(rucksack.number_of_item_types(), 8);
+        assert_eq!(shared_item_types.priority(), Some(16));
+        assert_eq!(
+            parse_items("aZb").priorities().collect::<Vec<u8>>(),
+            vec![1, 2, 52]
+        );
+        assert_eq!(parse_items("ab").priority(), None);
+        assert!((parse_items("ab") & parse_items("c")).is_empty());
+    }
+
     #[test]
     fn test_get_intersection_with_no_common_item_type() {
-        let mut group = vec![
-            HashSet::from(['a', 'b', 'c']),
-            HashSet::from(['d', 'e', 'f']),
-        ];
+        let group = vec![parse_items("abc"), parse_items("def")];
 
-        assert_eq!(get_intersection(&mut group), None)
+        assert!(get_intersection(&group).is_empty())
     }
 
     #[test]
     fn test_get_intersection_with_one_rucksack_in_group() {
-        let mut group = vec![HashSet::from(['a', 'b', 'c'])];
+        let group = vec![parse_items("abc")];
 
-        assert_eq!(get_intersection(&mut group), None)
+        assert_eq!(get_intersection(&group), parse_items("abc"))
     }
 
     #[test]
     fn test_get_intersection_with_empty_group() {
-        let mut group = vec![];
+        let group = vec![];
+
+        assert!(get_intersection(&group).is_empty())
+    }
+
+    #[test]
+    fn test_get_intersection_with_several_common_item_types() {
+        let group = vec![parse_items("abcZ"), parse_items("Zcde")];
 
-        assert_eq!(get_intersection(&mut group), None)
+        assert_eq!(get_intersection(&group).describe(), "c (3), Z (52)")
     }
 
     #[test]
@@ -172,6 +454,116 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
         assert_eq!(part1(TEST_INPUT), Ok(157.to_string()));
     }
 
+    #[test]
+    fn test_part1_invalid_rucksacks() {
+        assert_eq!(
+            part1("vJrwpWtwJgWrhcsFMMfFFhFp\nab1cda"),
+            Err("Unknown item type '1' at line 2".to_string())
+        );
+        assert_eq!(
+            part1("abcdé"),
+            Err("Odd number of items at line 1".to_string())
+        );
+        assert_eq!(
+            part1("abcd"),
+            Err("No item type shared by both compartments at line 1".to_string())
+        );
+        assert_eq!(
+            compartment_priorities("ab1cda\nabcde\nabcd\naa", Validation::Lenient),
+            Ok(Priorities {
+                sum: 2,
+                warnings: vec![
+                    "Unknown item type '1' at line 1".to_string(),
+                    "Odd number of items at line 2".to_string(),
+                    "No item type shared by both compartments at line 2".to_string(),
+                    "No item type shared by both compartments at line 3".to_string(),
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_part2_invalid_groups() {
+        assert_eq!(
+            part2(&(TEST_INPUT.to_string() + "\nabc\nabd")),
+            Err("Incomplete group of 2 rucksack(s) at lines 7-8".to_string())
+        );
+        assert_eq!(
+            part2("abc\nbcd\nbce"),
+            Err("Several item types shared by the group at lines 1-3: b (2), c (3)".to_string())
+        );
+        assert_eq!(
+            part2("abc\nbcd\nde"),
+            Err("No item type shared by the group at lines 1-3".to_string())
+        );
+        assert_eq!(
+            part2("ab\nb-\nb"),
+            Err("Unknown item type '-' at line 2".to_string())
+        );
+        assert_eq!(
+            part2("ab\nbé\nb"),
+            Err("Unknown item type 'é' at line 2".to_string())
+        );
+        assert_eq!(
+            group_priorities(&(TEST_INPUT.to_string() + "\nabc"), 3, Validation::Lenient),
+            Ok(Priorities {
+                sum: 70,
+                warnings: vec!["Incomplete group of 1 rucksack(s) at lines 7-7".to_string()]
+            })
+        );
+    }
+
+    #[test]
+    fn test_group_size() {
+        assert_eq!(
+            group_priorities(TEST_INPUT, 6, Validation::Strict),
+            Err("No item type shared by the group at lines 1-6".to_string())
+        );
+        assert_eq!(
+            group_priorities("ab\nbc\ncb\nbd", 2, Validation::Strict),
+            Ok(Priorities {
+                sum: 4,
+                warnings: vec![]
+            })
+        );
+        assert_eq!(
+            group_priorities("ab", 0, Validation::Lenient),
+            Err("Group size must be at least 1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_shared_item_types() {
+        let (groups, warnings) =
+            shared_item_types("abcZ\nZcde\nxy\nx", 2, Validation::Strict).unwrap();
+
+        assert_eq!(groups[0].to_string(), "Lines 1-2: c (3), Z (52)");
+        assert_eq!(groups[1].to_string(), "Lines 3-4: x (24)");
+        assert!(warnings.is_empty());
+        assert_eq!(
+            shared_item_types("ab\ncd", 2, Validation::Strict)
+                .unwrap()
+                .0[0]
+                .to_string(),
+            "Lines 1-2: none"
+        );
+    }
+
+    #[test]
+    fn test_shared_item_types_warnings() {
+        let (groups, warnings) = shared_item_types("ab\nbc\nc", 2, Validation::Lenient).unwrap();
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            warnings,
+            vec!["Incomplete group of 1 rucksack(s) at lines 3-3".to_string()]
+        );
+        assert_eq!(
+            shared_item_types("ab\nbc\nc", 2, Validation::Strict),
+            Err("Incomplete group of 1 rucksack(s) at lines 3-3".to_string())
+        );
+    }
+
     #[test]
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), Ok(70.to_string()));
@@ -60,82 +60,358 @@ So, in this example, the number of overlapping assignment pairs is 4.
 In how many assignment pairs do the ranges overlap?
 */
 
-use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+// Sections from `start` to `end`, both included, like `RangeInclusive`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
 
-fn sections_min_and_max(line: &str) -> Vec<u8> {
-    line.split(&['-', ','][..])
-        .map(|number| number.parse::<u8>())
-        .filter_map(Result::ok)
-        .collect()
+impl<T: Copy + Ord + fmt::Display> Interval<T> {
+    pub fn new(start: T, end: T) -> Result<Self, String> {
+        match start <= end {
+            true => Ok(Interval { start, end }),
+            false => Err(format!("Interval ends before it starts: {}-{}", start, end)),
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    pub fn contains(&self, other: &Interval<T>) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn contains_section(&self, section: T) -> bool {
+        self.start <= section && section <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        match self.overlaps(other) {
+            true => Some(Interval {
+                start: self.start.max(other.start),
+                end: self.end.min(other.end),
+            }),
+            false => None,
+        }
+    }
 }
 
-fn is_one_pair_fully_within_other_pair(sections_min_and_max: Vec<u8>) -> bool {
-    if sections_min_and_max.len() != 4 {
+impl Interval<u64> {
+    // Saturates for the interval of every section, which holds 2^64 of them.
+    pub fn len(&self) -> u64 {
+        (self.end - self.start).saturating_add(1)
+    }
+
+    // An interval always holds at least one section.
+    pub fn is_empty(&self) -> bool {
         false
-    } else {
-        if (sections_min_and_max[0] >= sections_min_and_max[2])
-            && (sections_min_and_max[1] <= sections_min_and_max[3])
+    }
+
+    // Single interval covering both, if they overlap or are next to each other.
+    pub fn union(&self, other: &Interval<u64>) -> Option<Interval<u64>> {
+        match self.start <= other.end.saturating_add(1) && other.start <= self.end.saturating_add(1)
         {
-            return true;
+            true => Some(Interval {
+                start: self.start.min(other.start),
+                end: self.end.max(other.end),
+            }),
+            false => None,
         }
+    }
+}
 
-        if (sections_min_and_max[2] >= sections_min_and_max[0])
-            && (sections_min_and_max[3] <= sections_min_and_max[1])
-        {
-            return true;
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl FromStr for Interval<u64> {
+    type Err = String;
+
+    fn from_str(sections: &str) -> Result<Self, Self::Err> {
+        let unknown_interval = || format!("Unknown sections: {}", sections);
+        let (start, end) = sections.split_once('-').ok_or_else(unknown_interval)?;
+
+        Interval::new(
+            start.parse::<u64>().map_err(|_| unknown_interval())?,
+            end.parse::<u64>().map_err(|_| unknown_interval())?,
+        )
+    }
+}
+
+// Assignments of a group of elves, from one line of the input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    pub line_number: usize,
+    pub assignments: Vec<Interval<u64>>,
+}
+
+impl Group {
+    pub fn parse(line_number: usize, line: &str) -> Result<Self, String> {
+        if line.trim().is_empty() {
+            return Err(format!("Missing assignments at line {}", line_number));
         }
 
-        false
+        let assignments = line
+            .split(',')
+            .map(|sections| {
+                sections
+                    .parse::<Interval<u64>>()
+                    .map_err(|error| format!("{} at line {}", error, line_number))
+            })
+            .collect::<Result<Vec<Interval<u64>>, String>>()?;
+
+        Ok(Group {
+            line_number,
+            assignments,
+        })
+    }
+
+    // Only an assignment going from the lowest to the highest section of the group
+    // can contain all the others.
+    pub fn has_assignment_containing_all_others(&self) -> bool {
+        let start = self.assignments.iter().map(|interval| interval.start).min();
+        let end = self.assignments.iter().map(|interval| interval.end).max();
+
+        self.assignments
+            .iter()
+            .any(|interval| Some(interval.start) == start && Some(interval.end) == end)
+    }
+
+    pub fn has_overlapping_pair(&self) -> bool {
+        let mut sorted_assignments = self.assignments.clone();
+
+        sorted_assignments.sort_by_key(|interval| interval.start);
+        sorted_assignments
+            .windows(2)
+            .scan(0, |highest_end, pair| {
+                *highest_end = (*highest_end).max(pair[0].end);
+                Some(pair[1].start <= *highest_end)
+            })
+            .any(|overlaps| overlaps)
+    }
+
+    // Every assignment overlaps the ones starting before it, except the ones
+    // ending before it starts.
+    pub fn number_of_overlapping_pairs(&self) -> usize {
+        let mut starts: Vec<u64> = self
+            .assignments
+            .iter()
+            .map(|interval| interval.start)
+            .collect();
+        let mut ends: Vec<u64> = self
+            .assignments
+            .iter()
+            .map(|interval| interval.end)
+            .collect();
+
+        starts.sort_unstable();
+        ends.sort_unstable();
+
+        starts
+            .iter()
+            .enumerate()
+            .map(|(index, start)| index - ends.partition_point(|end| end < start))
+            .sum()
     }
 }
 
-pub fn part1(input: &str) -> Result<String, String> {
-    let mut assignment_pairs_fully_in_another: i64 = 0;
+pub fn parse_groups(input: &str) -> Result<Vec<Group>, String> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| Group::parse(index + 1, line))
+        .collect()
+}
 
-    for line in input.lines() {
-        let sections_min_and_max = sections_min_and_max(line);
+pub fn overlap_report(input: &str) -> Result<String, String> {
+    Ok(parse_groups(input)?
+        .iter()
+        .map(|group| {
+            format!(
+                "Line {}: {} overlapping pair(s)\n",
+                group.line_number,
+                group.number_of_overlapping_pairs()
+            )
+        })
+        .collect())
+}
 
-        if is_one_pair_fully_within_other_pair(sections_min_and_max) {
-            assignment_pairs_fully_in_another += 1;
+// Merges intervals into disjoint intervals, sorted from the lowest sections.
+pub fn merge(intervals: &[Interval<u64>]) -> Vec<Interval<u64>> {
+    let mut sorted_intervals = intervals.to_vec();
+    let mut merged_intervals: Vec<Interval<u64>> = vec![];
+
+    sorted_intervals.sort_by_key(|interval| (interval.start, interval.end));
+
+    for interval in sorted_intervals {
+        match merged_intervals.last_mut() {
+            Some(last) => match last.union(&interval) {
+                Some(union) => *last = union,
+                None => merged_intervals.push(interval),
+            },
+            None => merged_intervals.push(interval),
         }
     }
 
-    Ok(assignment_pairs_fully_in_another.to_string())
+    merged_intervals
 }
 
-fn is_one_pair_overlapping_other_pair(sections_min_and_max: Vec<u8>) -> bool {
-    let first_pair: HashSet<u8> = (sections_min_and_max[0]..=sections_min_and_max[1])
-        .into_iter()
-        .collect();
+// Number of intervals covering every section, as consecutive intervals of sections
+// covered by the same number of intervals. Sections covered by none are left out.
+fn coverage_counts(intervals: &[Interval<u64>]) -> Vec<(Interval<u64>, usize)> {
+    // Every interval adds one from its start and removes one after its end.
+    let mut events: Vec<(u64, bool)> = vec![];
+    let mut counts: Vec<(Interval<u64>, usize)> = vec![];
+    let mut count: usize = 0;
 
-    let second_pair: HashSet<u8> = (sections_min_and_max[2]..=sections_min_and_max[3])
-        .into_iter()
-        .collect();
+    for interval in intervals {
+        events.push((interval.start, true));
+
+        if let Some(after_end) = interval.end.checked_add(1) {
+            events.push((after_end, false));
+        }
+    }
+
+    events.sort();
 
-    first_pair
-        .intersection(&second_pair)
-        .copied()
-        .next()
-        .is_some()
+    for (index, (section, is_start)) in events.iter().enumerate() {
+        match is_start {
+            true => count += 1,
+            false => count -= 1,
+        }
+
+        let next_section = events.get(index + 1).map(|(next, _)| *next);
+
+        if count > 0 && next_section != Some(*section) {
+            let end = next_section.map_or(u64::MAX, |next| next - 1);
+
+            counts.push((
+                Interval {
+                    start: *section,
+                    end,
+                },
+                count,
+            ));
+        }
+    }
+
+    counts
 }
 
-pub fn part2(input: &str) -> Result<String, String> {
-    let mut number_of_overlapping_pairs: i64 = 0;
+#[derive(Clone, Debug, PartialEq)]
+pub struct Coverage {
+    pub covered: Vec<Interval<u64>>,
+    // Sections between the lowest and highest covered sections that nobody covers.
+    pub uncovered: Vec<Interval<u64>>,
+    pub covered_more_than_once: Vec<Interval<u64>>,
+    // The lowest sections covered by the most assignments, and their number.
+    pub most_covered: Option<(Interval<u64>, usize)>,
+}
 
-    for line in input.lines() {
-        let sections_min_and_max = sections_min_and_max(line);
+impl Coverage {
+    pub fn new(assignments: &[Interval<u64>]) -> Self {
+        let covered = merge(assignments);
+        let counts = coverage_counts(assignments);
+        let uncovered = covered
+            .windows(2)
+            .map(|pair| Interval {
+                start: pair[0].end + 1,
+                end: pair[1].start - 1,
+            })
+            .collect();
+        let covered_more_than_once: Vec<Interval<u64>> = counts
+            .iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(interval, _)| *interval)
+            .collect();
+        let most_covered = counts.iter().rev().max_by_key(|(_, count)| *count).copied();
+
+        Coverage {
+            covered,
+            uncovered,
+            covered_more_than_once: merge(&covered_more_than_once),
+            most_covered,
+        }
+    }
+}
 
-        if is_one_pair_overlapping_other_pair(sections_min_and_max) {
-            number_of_overlapping_pairs += 1;
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let list = |intervals: &[Interval<u64>]| -> String {
+            match intervals.is_empty() {
+                true => "none".to_string(),
+                false => intervals
+                    .iter()
+                    .map(|interval| interval.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            }
+        };
+
+        writeln!(f, "Covered: {}", list(&self.covered))?;
+        writeln!(f, "Uncovered: {}", list(&self.uncovered))?;
+        writeln!(
+            f,
+            "Covered more than once: {}",
+            list(&self.covered_more_than_once)
+        )?;
+
+        match self.most_covered {
+            Some((interval, count)) => {
+                writeln!(f, "Most covered: {} ({} assignments)", interval, count)
+            }
+            None => writeln!(f, "Most covered: none"),
         }
     }
+}
+
+pub fn analyze_coverage(input: &str) -> Result<Coverage, String> {
+    let assignments: Vec<Interval<u64>> = parse_groups(input)?
+        .into_iter()
+        .flat_map(|group| group.assignments)
+        .collect();
+
+    Ok(Coverage::new(&assignments))
+}
+
+fn count_groups(input: &str, predicate: fn(&Group) -> bool) -> Result<String, String> {
+    let groups = parse_groups(input)?;
+
+    Ok(groups
+        .iter()
+        .filter(|group| predicate(group))
+        .count()
+        .to_string())
+}
 
-    Ok(number_of_overlapping_pairs.to_string())
+pub fn part1(input: &str) -> Result<String, String> {
+    count_groups(input, Group::has_assignment_containing_all_others)
+}
+
+pub fn part2(input: &str) -> Result<String, String> {
+    count_groups(input, Group::has_overlapping_pair)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day4::{is_one_pair_fully_within_other_pair, part1, part2};
+    use crate::day4::{
+        analyze_coverage, merge, overlap_report, part1, part2, Coverage, Group, Interval,
+    };
 
     static TEST_INPUT: &str = "2-4,6-8
 2-3,4-5
@@ -145,8 +421,88 @@ mod tests {
 2-6,4-8";
 
     #[test]
-    fn test_is_one_pair_fully_within_other_pair() {
-        assert!(!is_one_pair_fully_within_other_pair(vec![1, 2, 3, 4, 5]))
+    fn test_interval() {
+        let interval = Interval::new(2, 6).unwrap();
+        let other = Interval::new(4, 8).unwrap();
+
+        assert!(interval.contains(&Interval::new(3, 6).unwrap()));
+        assert!(!interval.contains(&other));
+        assert!(interval.contains_section(2));
+        assert!(interval.overlaps(&other));
+        assert!(!interval.overlaps(&Interval::new(7, 9).unwrap()));
+        assert_eq!(interval.intersection(&other), Interval::new(4, 6).ok());
+        assert_eq!(interval.intersection(&Interval::new(7, 9).unwrap()), None);
+        assert_eq!(interval.union(&other), Interval::new(2, 8).ok());
+        assert_eq!(
+            interval.union(&Interval::new(7, 9).unwrap()),
+            Interval::new(2, 9).ok()
+        );
+        assert_eq!(interval.union(&Interval::new(8, 9).unwrap()), None);
+        assert_eq!(interval.len(), 5);
+        assert_eq!(Interval::new(0, u64::MAX).unwrap().len(), u64::MAX);
+        assert_eq!(
+            Interval::new(5, 3),
+            Err("Interval ends before it starts: 5-3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_interval_from_str() {
+        assert_eq!(
+            "300-1000".parse::<Interval<u64>>(),
+            Interval::new(300, 1000)
+        );
+        assert_eq!(
+            "3-a".parse::<Interval<u64>>(),
+            Err("Unknown sections: 3-a".to_string())
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let intervals: Vec<Interval<u64>> = ["7-9", "1-2", "3-4", "8-12", "20-20"]
+            .iter()
+            .map(|sections| sections.parse().unwrap())
+            .collect();
+
+        assert_eq!(
+            merge(&intervals),
+            vec![
+                Interval::new(1, 4).unwrap(),
+                Interval::new(7, 12).unwrap(),
+                Interval::new(20, 20).unwrap()
+            ]
+        );
+        assert_eq!(merge(&[]), vec![]);
+    }
+
+    #[test]
+    fn test_analyze_coverage() {
+        assert_eq!(
+            analyze_coverage(TEST_INPUT).unwrap().to_string(),
+            "Covered: 2-9
+Uncovered: none
+Covered more than once: 2-8
+Most covered: 6-6 (8 assignments)
+"
+        );
+        assert_eq!(
+            analyze_coverage("1-3,10-12\n2-2,20-30\n25-25,2-3"),
+            Ok(Coverage {
+                covered: vec![
+                    Interval::new(1, 3).unwrap(),
+                    Interval::new(10, 12).unwrap(),
+                    Interval::new(20, 30).unwrap()
+                ],
+                uncovered: vec![Interval::new(4, 9).unwrap(), Interval::new(13, 19).unwrap()],
+                covered_more_than_once: vec![
+                    Interval::new(2, 3).unwrap(),
+                    Interval::new(25, 25).unwrap()
+                ],
+                most_covered: Some((Interval::new(2, 2).unwrap(), 3)),
+            })
+        );
+        assert_eq!(analyze_coverage("").unwrap().most_covered, None);
     }
 
     #[test]
@@ -154,6 +510,82 @@ mod tests {
         assert_eq!(part1(TEST_INPUT), Ok(2.to_string()));
     }
 
+    #[test]
+    fn test_part1_large_sections() {
+        assert_eq!(part1("300-400,350-360\n1-2,256-300"), Ok(1.to_string()));
+    }
+
+    #[test]
+    fn test_part1_malformed_lines() {
+        assert_eq!(
+            part1("2-4,6-8\n1-2,3-4;5-6"),
+            Err("Unknown sections: 3-4;5-6 at line 2".to_string())
+        );
+        assert_eq!(
+            part1("2-4\n\n1-2"),
+            Err("Missing assignments at line 2".to_string())
+        );
+        assert_eq!(
+            part2("4-2,6-8"),
+            Err("Interval ends before it starts: 4-2 at line 1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_groups() {
+        let group = Group::parse(1, "1-3,5-9,2-6,8-8,10-12").unwrap();
+
+        assert!(!group.has_assignment_containing_all_others());
+        assert!(group.has_overlapping_pair());
+        assert_eq!(group.number_of_overlapping_pairs(), 3);
+
+        let group = Group::parse(2, "3-4,1-9,5-5,9-9").unwrap();
+
+        assert!(group.has_assignment_containing_all_others());
+        assert_eq!(group.number_of_overlapping_pairs(), 3);
+
+        let group = Group::parse(3, "1-2,5-6,3-4").unwrap();
+
+        assert!(!group.has_overlapping_pair());
+        assert_eq!(group.number_of_overlapping_pairs(), 0);
+        assert!(Group::parse(4, "4-7")
+            .unwrap()
+            .has_assignment_containing_all_others());
+    }
+
+    #[test]
+    fn test_number_of_overlapping_pairs_against_every_pair() {
+        let groups = [
+            "1-1",
+            "2-4,6-8",
+            "5-7,7-9,1-8",
+            "1-2,3-4,5-6,7-8",
+            "10-20,15-15,20-30,31-31,0-9,9-10",
+            "4-4,4-4,4-4,4-4",
+            "300-400,350-360,399-500,100-299,299-300",
+        ];
+
+        for (index, line) in groups.iter().enumerate() {
+            let group = Group::parse(index + 1, line).unwrap();
+            let assignments = &group.assignments;
+            let expected = (0..assignments.len())
+                .flat_map(|i| (i + 1..assignments.len()).map(move |j| (i, j)))
+                .filter(|(i, j)| assignments[*i].overlaps(&assignments[*j]))
+                .count();
+
+            assert_eq!(group.number_of_overlapping_pairs(), expected);
+            assert_eq!(group.has_overlapping_pair(), expected > 0);
+        }
+    }
+
+    #[test]
+    fn test_overlap_report() {
+        assert_eq!(
+            overlap_report("2-4,6-8\n5-7,7-9,1-8"),
+            Ok("Line 1: 0 overlapping pair(s)\nLine 2: 3 overlapping pair(s)\n".to_string())
+        );
+    }
+
     #[test]
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), Ok(4.to_string()));
@@ -107,97 +107,705 @@ In this example, the CrateMover 9001 has put the crates in a totally different o
 Before the rearrangement process finishes, update your simulation so that the Elves know where they should stand to be ready to unload the final supplies. After the rearrangement procedure completes, what crate ends up on top of each stack?
 */
 
-use regex::Regex;
-use std::collections::VecDeque;
-
-fn solve(input: &str, group_crates_when_moving: bool) -> Result<String, String> {
-    let re_stacks = Regex::new(r"(?:\[|\s)(?P<crate>[A-Z]|\s)(?:\]|\s)\s?").unwrap();
-    let re_move = Regex::new(r"^move\s(?P<number_of_crates_to_move>\d+)\sfrom\s(?P<from_stack>\d+)\sto\s(?P<to_stack>\d+)$").unwrap();
-    let mut number_of_stacks: usize = 0;
-    let mut stacks: Vec<VecDeque<String>> = Vec::new();
-    let mut crates_at_the_top: String = String::from("");
-
-    for line in input.lines() {
-        if !line.contains("move") {
-            let matches: Vec<_> = re_stacks.captures_iter(line).collect();
-
-            if number_of_stacks == 0 {
-                match matches.len() {
-                    0 => return Err(format!("Unknown format for stacks: {}", line)),
-                    other => number_of_stacks += other,
-                }
+use std::fmt;
+use std::str::FromStr;
 
-                for _ in 0..number_of_stacks {
-                    stacks.push(VecDeque::new());
-                }
+// Stacks of crates, as drawn in the puzzle input. Every stack is kept from the
+// bottom to the top, so that crates are moved from the end of a vector to another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stacks {
+    stacks: Vec<Vec<String>>,
+}
+
+impl Stacks {
+    pub fn number_of_stacks(&self) -> usize {
+        self.stacks.len()
+    }
+
+    // Crates of a stack from the bottom to the top.
+    pub fn crates(&self, stack: usize) -> Option<Vec<&str>> {
+        self.stacks
+            .get(stack)
+            .map(|crates| crates.iter().map(|label| label.as_str()).collect())
+    }
+
+    // Up to `number_of_crates` crates from the top of a stack, from the bottom to the top.
+    pub fn top(&self, stack: usize, number_of_crates: usize) -> &[String] {
+        let crates = &self.stacks[stack];
+
+        &crates[crates.len() - number_of_crates.min(crates.len())..]
+    }
+
+    // Removes up to `number_of_crates` crates from the top of a stack, returned
+    // from the bottom to the top.
+    pub fn pick_up(&mut self, stack: usize, number_of_crates: usize) -> Vec<String> {
+        let crates = &mut self.stacks[stack];
+        let number_of_crates = number_of_crates.min(crates.len());
+
+        crates.split_off(crates.len() - number_of_crates)
+    }
+
+    // Puts crates ordered from the bottom to the top on top of a stack.
+    pub fn put_down(&mut self, stack: usize, crates: Vec<String>) {
+        self.stacks[stack].extend(crates);
+    }
+
+    // Moves up to `number_of_crates` crates from the top of a stack to the top of
+    // another without any intermediate allocation, reversing their order if needed.
+    // Crates moved onto their own stack stay where they are.
+    pub fn transfer(
+        &mut self,
+        from_stack: usize,
+        to_stack: usize,
+        number_of_crates: usize,
+        reverse: bool,
+    ) {
+        if from_stack == to_stack {
+            return;
+        }
+
+        let (from, to) = match from_stack < to_stack {
+            true => {
+                let (left, right) = self.stacks.split_at_mut(to_stack);
+                (&mut left[from_stack], &mut right[0])
             }
+            false => {
+                let (left, right) = self.stacks.split_at_mut(from_stack);
+                (&mut right[0], &mut left[to_stack])
+            }
+        };
+        let start = from.len() - number_of_crates.min(from.len());
 
-            for (index, cap) in matches.iter().enumerate() {
-                if &cap["crate"] != " " {
-                    stacks[index].push_back(String::from(&cap["crate"]));
-                }
+        match reverse {
+            true => to.extend(from.drain(start..).rev()),
+            false => to.extend(from.drain(start..)),
+        }
+    }
+
+    pub fn height(&self, stack: usize) -> usize {
+        self.stacks.get(stack).map_or(0, |crates| crates.len())
+    }
+
+    pub fn top_crates(&self) -> String {
+        self.stacks
+            .iter()
+            .filter_map(|crates| crates.last())
+            .map(|label| label.as_str())
+            .collect()
+    }
+
+    // Every column is wide enough for the longest label and stack number, plus brackets.
+    fn column_width(&self) -> usize {
+        let longest_label = self
+            .stacks
+            .iter()
+            .flatten()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(1);
+
+        longest_label.max(self.stacks.len().to_string().len()) + 2
+    }
+}
+
+// Parses the drawing of the stacks, the last line being the numbers of the stacks.
+impl FromStr for Stacks {
+    type Err = String;
+
+    fn from_str(drawing: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = drawing.lines().collect();
+        let (footer, crate_lines) = match lines.split_last() {
+            Some(split) => split,
+            None => return Err("Missing drawing of the stacks".to_string()),
+        };
+        // Positions of the first and last characters of every stack number.
+        let mut numbers: Vec<(usize, usize)> = Vec::new();
+        let footer_characters: Vec<char> = footer.chars().collect();
+        let mut position = 0;
+
+        while position < footer_characters.len() {
+            if footer_characters[position].is_whitespace() {
+                position += 1;
+                continue;
             }
-        } else {
-            let matches: Vec<_> = re_move.captures_iter(line).collect();
-            let mut from_stack: usize = 0;
-            let mut to_stack: usize = 0;
-            let mut number_of_crates_to_move: usize = 0;
-
-            if !matches.is_empty() {
-                if matches[0]["number_of_crates_to_move"]
-                    .parse::<usize>()
-                    .is_ok()
-                {
-                    number_of_crates_to_move = matches[0]["number_of_crates_to_move"]
-                        .parse::<usize>()
-                        .unwrap();
-                }
 
-                if matches[0]["from_stack"].parse::<usize>().is_ok() {
-                    from_stack = matches[0]["from_stack"].parse::<usize>().unwrap() - 1;
+            let start = position;
+
+            while position < footer_characters.len() && !footer_characters[position].is_whitespace()
+            {
+                position += 1;
+            }
+
+            let number: String = footer_characters[start..position].iter().collect();
+
+            if number != (numbers.len() + 1).to_string() {
+                return Err(format!("Unknown format for stacks: {}", footer));
+            }
+
+            numbers.push((start, position - 1));
+        }
+
+        if numbers.is_empty() {
+            return Err(format!("Unknown format for stacks: {}", footer));
+        }
+
+        let mut stacks: Vec<Vec<String>> = vec![Vec::new(); numbers.len()];
+
+        // Every crate belongs to the only stack whose number is below it.
+        for line in crate_lines {
+            let characters: Vec<char> = line.chars().collect();
+            let mut has_crate = vec![false; numbers.len()];
+            let mut position = 0;
+
+            while position < characters.len() {
+                if characters[position].is_whitespace() {
+                    position += 1;
+                    continue;
                 }
 
-                if matches[0]["to_stack"].parse::<usize>().is_ok() {
-                    to_stack = matches[0]["to_stack"].parse::<usize>().unwrap() - 1;
+                let start = position;
+                let end = match characters[start] {
+                    '[' => characters[start..]
+                        .iter()
+                        .position(|character| *character == ']')
+                        .map(|length| start + length),
+                    _ => None,
+                };
+                let stack = end.and_then(|end| {
+                    // Numbers are sorted by position: the first one ending at the
+                    // crate or after it must be the only one below it.
+                    let stack = numbers.partition_point(|(_, number_end)| *number_end < start);
+                    let is_below = |stack: usize| {
+                        numbers
+                            .get(stack)
+                            .is_some_and(|(number_start, _)| *number_start <= end)
+                    };
+
+                    match is_below(stack) && !is_below(stack + 1) {
+                        true => Some(stack),
+                        false => None,
+                    }
+                });
+
+                match (end, stack) {
+                    (Some(end), Some(stack)) if end > start + 1 && !has_crate[stack] => {
+                        stacks[stack].push(characters[start + 1..end].iter().collect());
+                        has_crate[stack] = true;
+                        position = end + 1;
+                    }
+                    _ => return Err(format!("Unknown format for stacks: {}", line)),
                 }
             }
 
-            if !group_crates_when_moving {
-                for _ in 0..number_of_crates_to_move {
-                    if let Some(crate_item) = &stacks[from_stack].pop_front() {
-                        stacks[to_stack].push_front(String::from(crate_item));
-                    }
+            for (index, crates) in stacks.iter().enumerate() {
+                if !crates.is_empty() && !has_crate[index] {
+                    return Err(format!(
+                        "Crate floating above stack {}: {}",
+                        index + 1,
+                        line
+                    ));
                 }
-            } else {
-                let mut temporary_stack = stacks[from_stack].clone();
+            }
+        }
+
+        // The drawing is read from the top to the bottom.
+        for crates in stacks.iter_mut() {
+            crates.reverse();
+        }
+
+        Ok(Stacks { stacks })
+    }
+}
+
+// Renders the drawing of the stacks in the format of the puzzle input.
+impl fmt::Display for Stacks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let column_width = self.column_width();
+        let height = self
+            .stacks
+            .iter()
+            .map(|crates| crates.len())
+            .max()
+            .unwrap_or(0);
+
+        for level in (0..height).rev() {
+            let cells: Vec<String> = self
+                .stacks
+                .iter()
+                .map(|crates| match crates.len() > level {
+                    true => format!(
+                        "{:^width$}",
+                        format!("[{}]", crates[level]),
+                        width = column_width
+                    ),
+                    false => " ".repeat(column_width),
+                })
+                .collect();
+
+            writeln!(f, "{}", cells.join(" "))?;
+        }
+
+        let numbers: Vec<String> = (1..=self.stacks.len())
+            .map(|number| format!("{:^width$}", number, width = column_width))
+            .collect();
+
+        writeln!(f, "{}", numbers.join(" "))
+    }
+}
+
+// Lines of the rearrangement procedure, with their line number in the input.
+pub type Procedure<'a> = Vec<(usize, &'a str)>;
+
+// Splits the input into the drawing of the stacks and the rearrangement procedure.
+pub fn parse_input(input: &str) -> Result<(Stacks, Procedure<'_>), String> {
+    let lines: Vec<&str> = input.lines().collect();
+    let drawing_length = lines
+        .iter()
+        .position(|line| line.trim().is_empty() || line.starts_with("move"))
+        .unwrap_or(lines.len());
+    let stacks = lines[..drawing_length].join("\n").parse::<Stacks>()?;
+    let procedure = lines
+        .iter()
+        .enumerate()
+        .skip(drawing_length)
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| (index + 1, *line))
+        .collect();
+
+    Ok((stacks, procedure))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Move {
+    pub number_of_crates: usize,
+    pub from_stack: usize,
+    pub to_stack: usize,
+}
 
-                stacks[from_stack] = temporary_stack.split_off(number_of_crates_to_move).clone();
-                temporary_stack.append(&mut stacks[to_stack]);
-                stacks[to_stack] = temporary_stack.clone();
+pub trait Crane {
+    fn move_crates(&mut self, stacks: &mut Stacks, crane_move: &Move);
+}
+
+// Moves one crate at a time, reversing the order of the moved crates. Crates
+// moved one at a time onto their own stack end up where they were.
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn move_crates(&mut self, stacks: &mut Stacks, crane_move: &Move) {
+        if crane_move.from_stack == crane_move.to_stack {
+            return;
+        }
+
+        stacks.transfer(
+            crane_move.from_stack,
+            crane_move.to_stack,
+            crane_move.number_of_crates,
+            true,
+        );
+    }
+}
+
+// Moves all the crates at once, keeping their order.
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn move_crates(&mut self, stacks: &mut Stacks, crane_move: &Move) {
+        stacks.transfer(
+            crane_move.from_stack,
+            crane_move.to_stack,
+            crane_move.number_of_crates,
+            false,
+        );
+    }
+}
+
+// Moves at most `batch_size` crates at once, keeping the order within a batch.
+pub struct BatchCrane {
+    pub batch_size: usize,
+}
+
+impl Crane for BatchCrane {
+    fn move_crates(&mut self, stacks: &mut Stacks, crane_move: &Move) {
+        let mut remaining = crane_move.number_of_crates;
+
+        while remaining > 0 && stacks.height(crane_move.from_stack) > 0 {
+            let batch_size = remaining.min(self.batch_size);
+
+            stacks.transfer(
+                crane_move.from_stack,
+                crane_move.to_stack,
+                batch_size,
+                false,
+            );
+            remaining -= batch_size;
+        }
+    }
+}
+
+// Keeps the order of the crates on odd moves and reverses it on even moves.
+#[derive(Default)]
+pub struct AlternatingCrane {
+    number_of_moves: usize,
+}
+
+impl Crane for AlternatingCrane {
+    fn move_crates(&mut self, stacks: &mut Stacks, crane_move: &Move) {
+        self.number_of_moves += 1;
+
+        match self.number_of_moves.is_multiple_of(2) {
+            true => CrateMover9000.move_crates(stacks, crane_move),
+            false => CrateMover9001.move_crates(stacks, crane_move),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CraneModel {
+    CrateMover9000,
+    CrateMover9001,
+    Batch(usize),
+    Alternating,
+}
+
+impl FromStr for CraneModel {
+    type Err = String;
+
+    fn from_str(model: &str) -> Result<Self, Self::Err> {
+        match model {
+            "9000" => Ok(CraneModel::CrateMover9000),
+            "9001" => Ok(CraneModel::CrateMover9001),
+            "alternating" => Ok(CraneModel::Alternating),
+            other => match other
+                .strip_prefix("batch:")
+                .map(|size| size.parse::<usize>())
+            {
+                Some(Ok(batch_size)) if batch_size > 0 => Ok(CraneModel::Batch(batch_size)),
+                _ => Err(format!("Unsupported crane model: {}", other)),
+            },
+        }
+    }
+}
+
+impl CraneModel {
+    pub fn build(&self) -> Box<dyn Crane> {
+        match self {
+            CraneModel::CrateMover9000 => Box::new(CrateMover9000),
+            CraneModel::CrateMover9001 => Box::new(CrateMover9001),
+            CraneModel::Batch(batch_size) => Box::new(BatchCrane {
+                batch_size: *batch_size,
+            }),
+            CraneModel::Alternating => Box::new(AlternatingCrane::default()),
+        }
+    }
+}
+
+// Strict validation rejects any invalid move, while clamping ignores the moves
+// that cannot be parsed and moves at most the crates available on a stack.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MoveValidation {
+    Strict,
+    Clamp,
+}
+
+fn parse_move(line_number: usize, line: &str, number_of_stacks: usize) -> Result<Move, String> {
+    let unknown_move = || format!("Unknown move at line {}: {}", line_number, line);
+    let mut numbers: Vec<usize> = Vec::with_capacity(3);
+
+    match line.split_ascii_whitespace().collect::<Vec<&str>>()[..] {
+        ["move", number_of_crates, "from", from_stack, "to", to_stack] => {
+            for number in [number_of_crates, from_stack, to_stack] {
+                numbers.push(number.parse::<usize>().map_err(|_| unknown_move())?);
             }
         }
+        _ => return Err(unknown_move()),
     }
 
-    for mut stack in stacks {
-        if let Some(crate_item) = &stack.pop_front() {
-            crates_at_the_top += crate_item;
+    for stack in &numbers[1..] {
+        if *stack == 0 || *stack > number_of_stacks {
+            return Err(format!(
+                "Unknown stack {} at line {}, there are {} stacks: {}",
+                stack, line_number, number_of_stacks, line
+            ));
+        }
+    }
+
+    Ok(Move {
+        number_of_crates: numbers[0],
+        from_stack: numbers[1] - 1,
+        to_stack: numbers[2] - 1,
+    })
+}
+
+// Returns the move to do for a line of the procedure, if any. Lines skipped
+// by clamping are reported as warnings.
+fn validate_move(
+    stacks: &Stacks,
+    line_number: usize,
+    line: &str,
+    validation: MoveValidation,
+    warnings: &mut Vec<String>,
+) -> Result<Option<Move>, String> {
+    let mut crane_move = match parse_move(line_number, line, stacks.number_of_stacks()) {
+        Ok(crane_move) => crane_move,
+        Err(error) if validation == MoveValidation::Clamp => {
+            warnings.push(error);
+            return Ok(None);
+        }
+        Err(error) => return Err(error),
+    };
+    let available = stacks.height(crane_move.from_stack);
+
+    if crane_move.number_of_crates > available {
+        match validation {
+            MoveValidation::Strict => {
+                return Err(format!(
+                    "Cannot move {} crates from stack {} at line {}, it holds {} crates",
+                    crane_move.number_of_crates,
+                    crane_move.from_stack + 1,
+                    line_number,
+                    available
+                ))
+            }
+            MoveValidation::Clamp => crane_move.number_of_crates = available,
         }
     }
 
-    Ok(crates_at_the_top)
+    Ok(Some(crane_move))
+}
+
+// Stacks at the end of the procedure, with the lines skipped by clamping.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rearrangement {
+    pub stacks: Stacks,
+    pub warnings: Vec<String>,
+}
+
+pub fn rearrange(
+    input: &str,
+    crane: &mut dyn Crane,
+    validation: MoveValidation,
+) -> Result<Rearrangement, String> {
+    let (mut stacks, procedure) = parse_input(input)?;
+    let mut warnings = vec![];
+
+    for (line_number, line) in procedure {
+        if let Some(crane_move) =
+            validate_move(&stacks, line_number, line, validation, &mut warnings)?
+        {
+            crane.move_crates(&mut stacks, &crane_move);
+        }
+    }
+
+    Ok(Rearrangement { stacks, warnings })
+}
+
+// A move done during a replay, with the crates picked up from the top of the
+// source stack and put down on the top of the target stack, from the bottom to the top.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    pub number: usize,
+    pub line_number: usize,
+    pub crane_move: Move,
+    pub picked_up: Vec<String>,
+    pub put_down: Vec<String>,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let labels = |crates: &[String]| -> String {
+            crates
+                .iter()
+                .map(|label| format!("[{}]", label))
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+
+        write!(
+            f,
+            "Move {} (line {}): {} from stack {} to stack {}, {} became {}",
+            self.number,
+            self.line_number,
+            self.crane_move.number_of_crates,
+            self.crane_move.from_stack + 1,
+            self.crane_move.to_stack + 1,
+            labels(&self.picked_up),
+            labels(&self.put_down)
+        )
+    }
+}
+
+// Runs a procedure move by move. Undone moves are redone from their recorded
+// crates, so that cranes keeping a state between moves are only used once per move.
+pub struct Replay<'a> {
+    stacks: Stacks,
+    procedure: Procedure<'a>,
+    next_line: usize,
+    crane: Box<dyn Crane>,
+    validation: MoveValidation,
+    warnings: Vec<String>,
+    done: Vec<Step>,
+    undone: Vec<Step>,
+    failed: bool,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(
+        input: &'a str,
+        model: CraneModel,
+        validation: MoveValidation,
+    ) -> Result<Self, String> {
+        let (stacks, procedure) = parse_input(input)?;
+
+        Ok(Replay {
+            stacks,
+            procedure,
+            next_line: 0,
+            crane: model.build(),
+            validation,
+            warnings: vec![],
+            done: vec![],
+            undone: vec![],
+            failed: false,
+        })
+    }
+
+    pub fn stacks(&self) -> &Stacks {
+        &self.stacks
+    }
+
+    // Lines skipped by clamping so far.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    // Number of moves done so far.
+    pub fn number_of_moves_done(&self) -> usize {
+        self.done.len()
+    }
+
+    pub fn step(&mut self) -> Result<Option<&Step>, String> {
+        if let Some(step) = self.undone.pop() {
+            self.stacks
+                .pick_up(step.crane_move.from_stack, step.picked_up.len());
+            self.stacks
+                .put_down(step.crane_move.to_stack, step.put_down.clone());
+            self.done.push(step);
+
+            return Ok(self.done.last());
+        }
+
+        while let Some((line_number, line)) = self.procedure.get(self.next_line).copied() {
+            self.next_line += 1;
+
+            let crane_move = match validate_move(
+                &self.stacks,
+                line_number,
+                line,
+                self.validation,
+                &mut self.warnings,
+            )? {
+                Some(crane_move) => crane_move,
+                None => continue,
+            };
+            let picked_up = self
+                .stacks
+                .top(crane_move.from_stack, crane_move.number_of_crates)
+                .to_vec();
+
+            self.crane.move_crates(&mut self.stacks, &crane_move);
+            self.done.push(Step {
+                number: self.done.len() + 1,
+                line_number,
+                crane_move,
+                picked_up,
+                put_down: self
+                    .stacks
+                    .top(crane_move.to_stack, crane_move.number_of_crates)
+                    .to_vec(),
+            });
+
+            return Ok(self.done.last());
+        }
+
+        Ok(None)
+    }
+
+    pub fn undo(&mut self) -> Option<&Step> {
+        let step = self.done.pop()?;
+
+        self.stacks
+            .pick_up(step.crane_move.to_stack, step.put_down.len());
+        self.stacks
+            .put_down(step.crane_move.from_stack, step.picked_up.clone());
+        self.undone.push(step);
+
+        self.undone.last()
+    }
+
+    // Goes forward or backward to the state right after the given move.
+    pub fn jump_to(&mut self, number: usize) -> Result<(), String> {
+        while self.number_of_moves_done() > number {
+            self.undo();
+        }
+
+        while self.number_of_moves_done() < number {
+            if self.step()?.is_none() {
+                return Err(format!(
+                    "Cannot jump to move {}, the procedure has {} moves",
+                    number,
+                    self.number_of_moves_done()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Iterates over every move with the state of the stacks after it, until the
+// end of the procedure or the first error.
+impl Iterator for Replay<'_> {
+    type Item = Result<(Step, Stacks), String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        match self.step() {
+            Ok(Some(step)) => {
+                let step = step.clone();
+                Some(Ok((step, self.stacks.clone())))
+            }
+            Ok(None) => None,
+            Err(error) => {
+                self.failed = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+pub fn solve(input: &str, model: CraneModel, validation: MoveValidation) -> Result<String, String> {
+    Ok(rearrange(input, model.build().as_mut(), validation)?
+        .stacks
+        .top_crates())
 }
 
 pub fn part1(input: &str) -> Result<String, String> {
-    solve(input, false)
+    solve(input, CraneModel::CrateMover9000, MoveValidation::Strict)
 }
 
 pub fn part2(input: &str) -> Result<String, String> {
-    solve(input, true)
+    solve(input, CraneModel::CrateMover9001, MoveValidation::Strict)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day5::{part1, part2};
+    use crate::day5::{
+        parse_input, part1, part2, rearrange, solve, AlternatingCrane, Crane, CraneModel, Move,
+        MoveValidation, Replay, Stacks,
+    };
 
     static TEST_INPUT: &str = "    [D]    
 [N] [C]    
@@ -237,9 +845,70 @@ move 1 from 1 to 2
         assert_eq!(part1(TEST_INPUT), Ok("CMZ".to_string()));
     }
 
+    #[test]
+    fn test_part1_move_onto_same_stack() {
+        assert_eq!(
+            part1("[A]\n[B]\n 1   2 \n\nmove 2 from 1 to 1\n"),
+            Ok("A".to_string())
+        );
+    }
+
     #[test]
     fn test_part1_bad_move() {
-        assert_eq!(part1(TEST_INPUT_BAD_MOVE), Ok("MZ".to_string()));
+        assert_eq!(
+            part1(TEST_INPUT_BAD_MOVE),
+            Err("Unknown move at line 9: move abc from 1 to 2".to_string())
+        );
+        assert_eq!(
+            solve(
+                TEST_INPUT_BAD_MOVE,
+                CraneModel::CrateMover9000,
+                MoveValidation::Clamp
+            ),
+            Ok("MZ".to_string())
+        );
+        assert_eq!(
+            rearrange(
+                TEST_INPUT_BAD_MOVE,
+                CraneModel::CrateMover9000.build().as_mut(),
+                MoveValidation::Clamp
+            )
+            .unwrap()
+            .warnings,
+            vec!["Unknown move at line 9: move abc from 1 to 2".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_part1_move_with_tabs() {
+        let input = TEST_INPUT.replace("move 1 from 2 to 1", "move\t1 from  2\tto 1");
+        assert_eq!(part1(&input), Ok("CMZ".to_string()));
+    }
+
+    #[test]
+    fn test_part1_unknown_stack() {
+        assert_eq!(
+            part1(&TEST_INPUT.replace("move 1 from 1 to 2", "move 1 from 1 to 4")),
+            Err("Unknown stack 4 at line 9, there are 3 stacks: move 1 from 1 to 4".to_string())
+        );
+        assert_eq!(
+            part1(&TEST_INPUT.replace("move 1 from 2 to 1", "move 1 from 0 to 1")),
+            Err("Unknown stack 0 at line 6, there are 3 stacks: move 1 from 0 to 1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_part2_too_many_crates() {
+        let input = TEST_INPUT.replace("move 2 from 2 to 1", "move 5 from 2 to 1");
+
+        assert_eq!(
+            part2(&input),
+            Err("Cannot move 5 crates from stack 2 at line 8, it holds 2 crates".to_string())
+        );
+        assert_eq!(
+            solve(&input, CraneModel::CrateMover9001, MoveValidation::Clamp),
+            Ok("MCD".to_string())
+        );
     }
 
     #[test]
@@ -250,6 +919,272 @@ move 1 from 1 to 2
         )
     }
 
+    static TEST_DRAWING_MANY_STACKS: &str = "                                        [Q]      
+[AB] [C]                                [R]      
+[D]  [E]  [F]  [G]  [H]  [I]  [J]  [K]  [L]  [M] 
+ 1    2    3    4    5    6    7    8    9    10 
+";
+
+    #[test]
+    fn test_parse_input() {
+        let (stacks, procedure) = parse_input(TEST_INPUT).unwrap();
+
+        assert_eq!(stacks.number_of_stacks(), 3);
+        assert_eq!(stacks.crates(0), Some(vec!["Z", "N"]));
+        assert_eq!(stacks.crates(1), Some(vec!["M", "C", "D"]));
+        assert_eq!(stacks.crates(3), None);
+        assert_eq!(stacks.top_crates(), "NDP");
+        assert_eq!(procedure.len(), 4);
+        assert_eq!(procedure[0], (6, "move 1 from 2 to 1"));
+    }
+
+    #[test]
+    fn test_stacks_round_trip() {
+        let drawing = TEST_INPUT.split("\n\n").next().unwrap().to_string() + "\n";
+        let stacks = drawing.parse::<Stacks>().unwrap();
+
+        assert_eq!(stacks.to_string(), drawing);
+        assert_eq!(stacks.to_string().parse::<Stacks>(), Ok(stacks));
+    }
+
+    #[test]
+    fn test_stacks_with_many_stacks_and_long_labels() {
+        let stacks = TEST_DRAWING_MANY_STACKS.parse::<Stacks>().unwrap();
+
+        assert_eq!(stacks.number_of_stacks(), 10);
+        assert_eq!(stacks.crates(0), Some(vec!["D", "AB"]));
+        assert_eq!(stacks.crates(8), Some(vec!["L", "R", "Q"]));
+        assert_eq!(stacks.crates(9), Some(vec!["M"]));
+        assert_eq!(stacks.to_string(), TEST_DRAWING_MANY_STACKS);
+        assert_eq!(
+            TEST_DRAWING_MANY_STACKS
+                .lines()
+                .map(|line| line.trim_end())
+                .collect::<Vec<&str>>()
+                .join("\n")
+                .parse::<Stacks>(),
+            Ok(stacks)
+        );
+    }
+
+    #[test]
+    fn test_stacks_with_ten_stacks_in_puzzle_layout() {
+        let drawing = "                                    [K]
+[A] [B] [C] [D] [E] [F] [G] [H] [I] [J]
+ 1   2   3   4   5   6   7   8   9  10 ";
+        let stacks = drawing.parse::<Stacks>().unwrap();
+
+        assert_eq!(stacks.number_of_stacks(), 10);
+        assert_eq!(stacks.crates(8), Some(vec!["I"]));
+        assert_eq!(stacks.crates(9), Some(vec!["J", "K"]));
+        assert_eq!(stacks.top_crates(), "ABCDEFGHIK");
+    }
+
+    #[test]
+    fn test_stacks_with_bad_drawings() {
+        assert_eq!(
+            "[A] [B]\n 1   3 ".parse::<Stacks>(),
+            Err("Unknown format for stacks:  1   3 ".to_string())
+        );
+        assert_eq!(
+            "[A]    \n    [B]\n 1   2 ".parse::<Stacks>(),
+            Err("Crate floating above stack 1:     [B]".to_string())
+        );
+        assert_eq!(
+            "".parse::<Stacks>(),
+            Err("Missing drawing of the stacks".to_string())
+        );
+        assert_eq!(
+            "  [A]  \n 1   2 ".parse::<Stacks>(),
+            Err("Unknown format for stacks:   [A]  ".to_string())
+        );
+        assert_eq!(
+            "[A] B\n 1   2 ".parse::<Stacks>(),
+            Err("Unknown format for stacks: [A] B".to_string())
+        );
+    }
+
+    #[test]
+    fn test_stacks_transfer() {
+        let (mut stacks, _) = parse_input(TEST_INPUT).unwrap();
+
+        stacks.transfer(1, 0, 2, false);
+        assert_eq!(stacks.crates(0), Some(vec!["Z", "N", "C", "D"]));
+        assert_eq!(stacks.crates(1), Some(vec!["M"]));
+
+        stacks.transfer(0, 2, 3, true);
+        assert_eq!(stacks.crates(0), Some(vec!["Z"]));
+        assert_eq!(stacks.crates(2), Some(vec!["P", "D", "C", "N"]));
+
+        stacks.transfer(2, 2, 2, true);
+        assert_eq!(stacks.crates(2), Some(vec!["P", "D", "C", "N"]));
+
+        stacks.transfer(1, 0, 5, false);
+        assert_eq!(stacks.crates(0), Some(vec!["Z", "M"]));
+        assert_eq!(stacks.top(0, 1), ["M".to_string()]);
+    }
+
+    #[test]
+    fn test_crane_models() {
+        assert_eq!(
+            solve(TEST_INPUT, CraneModel::Batch(1), MoveValidation::Strict),
+            Ok("CMZ".to_string())
+        );
+        assert_eq!(
+            solve(TEST_INPUT, CraneModel::Batch(3), MoveValidation::Strict),
+            Ok("MCD".to_string())
+        );
+        assert_eq!(
+            solve(TEST_INPUT, CraneModel::Batch(2), MoveValidation::Strict),
+            Ok("MCZ".to_string())
+        );
+        assert_eq!(
+            solve(TEST_INPUT, CraneModel::Alternating, MoveValidation::Strict),
+            Ok("MCZ".to_string())
+        );
+    }
+
+    #[test]
+    fn test_alternating_crane() {
+        let mut stacks = "[A]    \n[B]    \n 1   2 ".parse::<Stacks>().unwrap();
+        let mut crane = AlternatingCrane::default();
+
+        crane.move_crates(
+            &mut stacks,
+            &Move {
+                number_of_crates: 2,
+                from_stack: 0,
+                to_stack: 1,
+            },
+        );
+        assert_eq!(stacks.crates(1), Some(vec!["B", "A"]));
+
+        crane.move_crates(
+            &mut stacks,
+            &Move {
+                number_of_crates: 2,
+                from_stack: 1,
+                to_stack: 0,
+            },
+        );
+        assert_eq!(stacks.crates(0), Some(vec!["A", "B"]));
+    }
+
+    #[test]
+    fn test_crane_model_from_str() {
+        assert_eq!("9000".parse::<CraneModel>(), Ok(CraneModel::CrateMover9000));
+        assert_eq!("batch:4".parse::<CraneModel>(), Ok(CraneModel::Batch(4)));
+        assert_eq!(
+            "batch:0".parse::<CraneModel>(),
+            Err("Unsupported crane model: batch:0".to_string())
+        );
+        assert_eq!(
+            "alternating".parse::<CraneModel>(),
+            Ok(CraneModel::Alternating)
+        );
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut replay = Replay::new(
+            TEST_INPUT,
+            CraneModel::CrateMover9000,
+            MoveValidation::Strict,
+        )
+        .unwrap();
+        let states: Vec<String> = replay
+            .by_ref()
+            .map(|state| state.unwrap().1.to_string())
+            .collect();
+
+        assert_eq!(states.len(), 4);
+        assert_eq!(
+            states[1],
+            "        [Z]\n        [N]\n    [C] [D]\n    [M] [P]\n 1   2   3 \n"
+        );
+        assert_eq!(replay.stacks().top_crates(), "CMZ");
+    }
+
+    #[test]
+    fn test_replay_step_and_undo() {
+        let mut replay = Replay::new(
+            TEST_INPUT,
+            CraneModel::CrateMover9000,
+            MoveValidation::Strict,
+        )
+        .unwrap();
+
+        replay.step().unwrap();
+        assert_eq!(
+            replay.step().unwrap().unwrap().to_string(),
+            "Move 2 (line 7): 3 from stack 1 to stack 3, [Z] [N] [D] became [D] [N] [Z]"
+        );
+        assert_eq!(replay.stacks().top_crates(), "CZ");
+
+        assert_eq!(replay.undo().unwrap().number, 2);
+        assert_eq!(replay.number_of_moves_done(), 1);
+        assert_eq!(replay.stacks().top_crates(), "DCP");
+
+        replay.jump_to(4).unwrap();
+        assert_eq!(replay.stacks().top_crates(), "CMZ");
+
+        replay.jump_to(0).unwrap();
+        assert_eq!(
+            replay.stacks().to_string(),
+            TEST_INPUT.split("\n\n").next().unwrap().to_string() + "\n"
+        );
+        assert_eq!(
+            replay.jump_to(5),
+            Err("Cannot jump to move 5, the procedure has 4 moves".to_string())
+        );
+    }
+
+    #[test]
+    fn test_replay_with_alternating_crane() {
+        let mut replay =
+            Replay::new(TEST_INPUT, CraneModel::Alternating, MoveValidation::Strict).unwrap();
+
+        replay.jump_to(4).unwrap();
+        replay.jump_to(1).unwrap();
+        replay.jump_to(4).unwrap();
+        assert_eq!(replay.stacks().top_crates(), "MCZ");
+    }
+
+    #[test]
+    fn test_replay_warnings() {
+        let mut replay = Replay::new(
+            TEST_INPUT_BAD_MOVE,
+            CraneModel::CrateMover9000,
+            MoveValidation::Clamp,
+        )
+        .unwrap();
+
+        replay.jump_to(3).unwrap();
+        assert!(replay.warnings().is_empty());
+        assert!(replay.step().unwrap().is_none());
+        assert_eq!(
+            replay.warnings(),
+            ["Unknown move at line 9: move abc from 1 to 2".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_replay_stops_at_first_error() {
+        let replay = Replay::new(
+            TEST_INPUT_BAD_MOVE,
+            CraneModel::CrateMover9000,
+            MoveValidation::Strict,
+        )
+        .unwrap();
+        let states: Vec<Result<_, _>> = replay.collect();
+
+        assert_eq!(states.len(), 4);
+        assert_eq!(
+            states[3],
+            Err("Unknown move at line 9: move abc from 1 to 2".to_string())
+        );
+    }
+
     #[test]
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), Ok("MCD".to_string()));
@@ -49,49 +49,300 @@ Here are the first positions of start-of-message markers for all of the above ex
 How many characters need to be processed before the first start-of-message marker is detected?
 */
 
-use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::str::FromStr;
 
 const START_OF_PACKET_MARKER_LENGTH: usize = 4;
 const START_OF_MESSAGE_MARKER_LENGTH: usize = 14;
+// A marker cannot be longer than the number of distinct bytes.
+const MAX_MARKER_LENGTH: usize = 256;
+const CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MarkerKind {
+    StartOfPacket,
+    StartOfMessage,
+}
+
+impl FromStr for MarkerKind {
+    type Err = String;
+
+    fn from_str(kind: &str) -> Result<Self, Self::Err> {
+        match kind {
+            "start-of-packet" => Ok(MarkerKind::StartOfPacket),
+            "start-of-message" => Ok(MarkerKind::StartOfMessage),
+            other => Err(format!("Unsupported marker kind: {}", other)),
+        }
+    }
+}
+
+impl MarkerKind {
+    pub fn length(&self) -> usize {
+        match self {
+            MarkerKind::StartOfPacket => START_OF_PACKET_MARKER_LENGTH,
+            MarkerKind::StartOfMessage => START_OF_MESSAGE_MARKER_LENGTH,
+        }
+    }
+}
+
+// A marker spans the bytes `start..end` of the datastream, `end` being the number
+// of bytes processed when it is detected.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Marker {
+    pub start: usize,
+    pub end: usize,
+}
+
+fn validate_marker_length(marker_length: usize) -> Result<(), String> {
+    match marker_length == 0 || marker_length > MAX_MARKER_LENGTH {
+        true => Err(format!(
+            "Marker length must be between 1 and {}: {}",
+            MAX_MARKER_LENGTH, marker_length
+        )),
+        false => Ok(()),
+    }
+}
+
+// Number of occurrences of every byte in a window, and number of distinct bytes.
+#[derive(Clone)]
+struct ByteCounts {
+    counts: [u32; 256],
+    number_of_distinct_bytes: u32,
+}
+
+impl ByteCounts {
+    fn new() -> Self {
+        ByteCounts {
+            counts: [0; 256],
+            number_of_distinct_bytes: 0,
+        }
+    }
+
+    // Counts are updated without branching, as bytes are hard to predict.
+    #[inline(always)]
+    fn add(&mut self, byte: u8) {
+        let count = &mut self.counts[byte as usize];
+
+        self.number_of_distinct_bytes += (*count == 0) as u32;
+        *count += 1;
+    }
+
+    #[inline(always)]
+    fn remove(&mut self, byte: u8) {
+        let count = &mut self.counts[byte as usize];
+
+        *count -= 1;
+        self.number_of_distinct_bytes -= (*count == 0) as u32;
+    }
+}
 
-fn solve(input: &str, marker_length: usize) -> usize {
-    for (index, window) in input
-        .chars()
-        .collect::<Vec<char>>()
-        .windows(marker_length)
+// Grows a window of distinct bytes over the datastream. Every byte moves the
+// start of the window right after its previous occurrence, so that each byte is
+// only looked at once whatever the length of the marker. Once a marker is found,
+// the window restarts right after it, so that markers never overlap.
+pub struct Markers<'a> {
+    datastream: &'a [u8],
+    marker_length: usize,
+    // Position right after the last occurrence of every byte.
+    next_to_last_seen: [usize; 256],
+    window_start: usize,
+    position: usize,
+}
+
+impl<'a> Markers<'a> {
+    pub fn new(datastream: &'a [u8], marker_length: usize) -> Result<Self, String> {
+        validate_marker_length(marker_length)?;
+
+        Ok(Markers {
+            datastream,
+            marker_length,
+            next_to_last_seen: [0; 256],
+            window_start: 0,
+            position: 0,
+        })
+    }
+}
+
+impl Iterator for Markers<'_> {
+    type Item = Marker;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let marker_length = self.marker_length;
+        let next_to_last_seen = &mut self.next_to_last_seen;
+        let mut window_start = self.window_start;
+
+        for (position, byte) in (self.position..).zip(&self.datastream[self.position..]) {
+            let last_seen = &mut next_to_last_seen[*byte as usize];
+
+            window_start = window_start.max(*last_seen);
+            *last_seen = position + 1;
+
+            if position + 1 - window_start == marker_length {
+                self.window_start = position + 1;
+                self.position = position + 1;
+
+                return Some(Marker {
+                    start: window_start,
+                    end: position + 1,
+                });
+            }
+        }
+
+        self.window_start = window_start;
+        self.position = self.datastream.len();
+        None
+    }
+}
+
+// Number of bytes processed until the first marker is detected, if any.
+pub fn find_marker(datastream: &[u8], marker_length: usize) -> Result<Option<usize>, String> {
+    Ok(Markers::new(datastream, marker_length)?
+        .next()
+        .map(|marker| marker.end))
+}
+
+// A message starts with its marker, and its payload runs until the next marker
+// or the end of the datastream.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message<'a> {
+    pub marker: Marker,
+    pub payload: &'a [u8],
+}
+
+pub fn split_messages(datastream: &[u8], kind: MarkerKind) -> Result<Vec<Message<'_>>, String> {
+    let markers: Vec<Marker> = Markers::new(datastream, kind.length())?.collect();
+
+    Ok(markers
+        .iter()
         .enumerate()
-    {
-        let mut signal_buffer = HashSet::new();
+        .map(|(index, marker)| Message {
+            marker: *marker,
+            payload: &datastream[marker.end
+                ..markers
+                    .get(index + 1)
+                    .map_or(datastream.len(), |next| next.start)],
+        })
+        .collect())
+}
+
+// Detects markers in a datastream received in chunks of any size, keeping the
+// bytes of the current window between chunks.
+pub struct MarkerDetector {
+    marker_length: usize,
+    byte_counts: ByteCounts,
+    window: VecDeque<u8>,
+    number_of_bytes: usize,
+}
+
+impl MarkerDetector {
+    pub fn new(kind: MarkerKind) -> Self {
+        MarkerDetector {
+            marker_length: kind.length(),
+            byte_counts: ByteCounts::new(),
+            window: VecDeque::with_capacity(kind.length() + 1),
+            number_of_bytes: 0,
+        }
+    }
+
+    pub fn with_marker_length(marker_length: usize) -> Result<Self, String> {
+        validate_marker_length(marker_length)?;
 
-        for character in window {
-            signal_buffer.insert(character);
+        Ok(MarkerDetector {
+            marker_length,
+            byte_counts: ByteCounts::new(),
+            window: VecDeque::with_capacity(marker_length + 1),
+            number_of_bytes: 0,
+        })
+    }
+
+    // Number of bytes pushed so far.
+    pub fn number_of_bytes(&self) -> usize {
+        self.number_of_bytes
+    }
+
+    // Returns the markers completed by the chunk, positioned in the whole datastream.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<Marker> {
+        let mut markers: Vec<Marker> = vec![];
+
+        for byte in chunk {
+            self.byte_counts.add(*byte);
+            self.window.push_back(*byte);
+            self.number_of_bytes += 1;
+
+            if self.window.len() > self.marker_length {
+                if let Some(leaving_byte) = self.window.pop_front() {
+                    self.byte_counts.remove(leaving_byte);
+                }
+            }
+
+            if self.byte_counts.number_of_distinct_bytes as usize == self.marker_length {
+                markers.push(Marker {
+                    start: self.number_of_bytes - self.marker_length,
+                    end: self.number_of_bytes,
+                });
+
+                for byte in self.window.drain(..) {
+                    self.byte_counts.remove(byte);
+                }
+            }
         }
 
-        if signal_buffer.len() == marker_length {
-            return index + marker_length;
+        markers
+    }
+}
+
+// Reports every marker of a datastream as soon as it is detected.
+pub fn detect_markers(
+    kind: MarkerKind,
+    mut datastream: impl Read,
+    output: &mut impl Write,
+) -> Result<usize, String> {
+    let mut detector = MarkerDetector::new(kind);
+    let mut chunk = vec![0; CHUNK_SIZE];
+    let mut number_of_markers: usize = 0;
+
+    loop {
+        let length = match datastream.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(length) => length,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.to_string()),
+        };
+
+        for marker in detector.push(&chunk[..length]) {
+            writeln!(output, "Marker at {}", marker.end).map_err(|err| err.to_string())?;
+            number_of_markers += 1;
         }
+
+        output.flush().map_err(|err| err.to_string())?;
     }
 
-    0
+    Ok(number_of_markers)
 }
 
-pub fn part1(input: &str) -> Result<String, String> {
-    match solve(input, START_OF_PACKET_MARKER_LENGTH) {
-        0 => Err("Could not solve puzzle".to_string()),
-        solution => Ok(solution.to_string()),
+fn solve(input: &str, kind: MarkerKind) -> Result<String, String> {
+    match find_marker(input.as_bytes(), kind.length())? {
+        Some(solution) => Ok(solution.to_string()),
+        None => Err("Could not solve puzzle".to_string()),
     }
 }
 
+pub fn part1(input: &str) -> Result<String, String> {
+    solve(input, MarkerKind::StartOfPacket)
+}
+
 pub fn part2(input: &str) -> Result<String, String> {
-    match solve(input, START_OF_MESSAGE_MARKER_LENGTH) {
-        0 => Err("Could not solve puzzle".to_string()),
-        solution => Ok(solution.to_string()),
-    }
+    solve(input, MarkerKind::StartOfMessage)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day6::{part1, part2};
+    use crate::day6::{
+        detect_markers, find_marker, part1, part2, split_messages, Marker, MarkerDetector,
+        MarkerKind, Markers, Message,
+    };
+    use std::collections::HashSet;
 
     static TEST_INPUT_1: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb
 ";
@@ -175,4 +426,132 @@ mod tests {
             Err("Could not solve puzzle".to_string())
         );
     }
+
+    #[test]
+    fn test_find_marker_lengths() {
+        let datastream: Vec<u8> = (0..=255).chain(0..=255).collect();
+
+        assert_eq!(find_marker(b"aab", 1), Ok(Some(1)));
+        assert_eq!(find_marker(&datastream, 256), Ok(Some(256)));
+        assert_eq!(find_marker(&datastream[1..], 256), Ok(Some(256)));
+        assert_eq!(find_marker(b"abab", 3), Ok(None));
+        assert_eq!(
+            find_marker(b"abc", 0),
+            Err("Marker length must be between 1 and 256: 0".to_string())
+        );
+        assert_eq!(
+            find_marker(b"abc", 257),
+            Err("Marker length must be between 1 and 256: 257".to_string())
+        );
+    }
+
+    #[test]
+    fn test_find_marker_against_every_window() {
+        for datastream in [
+            TEST_INPUT_1,
+            TEST_INPUT_2,
+            TEST_INPUT_3,
+            TEST_INPUT_4,
+            TEST_INPUT_5,
+            TEST_INPUT_BAD,
+            "abcabcdabcdeabcdefaaaaaaabcdefghijklmnopqrstuvwxyz",
+        ] {
+            let datastream = datastream.trim_end().as_bytes();
+
+            for marker_length in 1..=27 {
+                let expected = datastream
+                    .windows(marker_length)
+                    .position(|window| {
+                        window.iter().collect::<HashSet<&u8>>().len() == marker_length
+                    })
+                    .map(|index| index + marker_length);
+
+                assert_eq!(find_marker(datastream, marker_length), Ok(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_markers() {
+        assert_eq!(
+            Markers::new(b"aabcdbcdeff", 3)
+                .unwrap()
+                .collect::<Vec<Marker>>(),
+            vec![
+                Marker { start: 1, end: 4 },
+                Marker { start: 4, end: 7 },
+                Marker { start: 7, end: 10 }
+            ]
+        );
+        assert_eq!(Markers::new(b"aaaa", 2).unwrap().next(), None);
+    }
+
+    #[test]
+    fn test_split_messages() {
+        let datastream = b"aaaabcdefghijklmnhello, aabcdefghijklmnbye";
+
+        assert_eq!(
+            split_messages(datastream, MarkerKind::StartOfMessage),
+            Ok(vec![
+                Message {
+                    marker: Marker { start: 3, end: 17 },
+                    payload: b"hello, a",
+                },
+                Message {
+                    marker: Marker { start: 25, end: 39 },
+                    payload: b"bye",
+                }
+            ])
+        );
+        assert_eq!(
+            split_messages(b"aaaa", MarkerKind::StartOfPacket),
+            Ok(vec![])
+        );
+    }
+
+    #[test]
+    fn test_marker_detector_across_chunks() {
+        let datastream = b"aaaabcdefghijklmnhello, aabcdefghijklmnbye";
+        let expected: Vec<Marker> = Markers::new(datastream, 14).unwrap().collect();
+
+        for chunk_size in 1..=datastream.len() {
+            let mut detector = MarkerDetector::new(MarkerKind::StartOfMessage);
+            let markers: Vec<Marker> = datastream
+                .chunks(chunk_size)
+                .flat_map(|chunk| detector.push(chunk))
+                .collect();
+
+            assert_eq!(markers, expected);
+            assert_eq!(detector.number_of_bytes(), datastream.len());
+        }
+
+        let mut detector = MarkerDetector::with_marker_length(4).unwrap();
+
+        assert_eq!(detector.push(b"mjqj"), vec![]);
+        assert_eq!(detector.push(b"pq"), vec![]);
+        assert_eq!(detector.push(b"mg"), vec![Marker { start: 3, end: 7 }]);
+        assert!(MarkerDetector::with_marker_length(0).is_err());
+    }
+
+    #[test]
+    fn test_detect_markers() {
+        let mut output: Vec<u8> = vec![];
+
+        assert_eq!(
+            detect_markers(
+                MarkerKind::StartOfPacket,
+                TEST_INPUT_1.as_bytes(),
+                &mut output
+            ),
+            Ok(7)
+        );
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Marker at 7\nMarker at 11\nMarker at 15\nMarker at 19\nMarker at 23\nMarker at 27\nMarker at 31\n"
+        );
+        assert_eq!(
+            "start-of-message".parse::<MarkerKind>(),
+            Ok(MarkerKind::StartOfMessage)
+        );
+    }
 }
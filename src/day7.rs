@@ -104,95 +104,595 @@ const MAX_DIRECTORY_SIZE_TO_CONSIDER: u64 = 100_000;
 const TOTAL_DISK_SPACE: u64 = 70_000_000;
 const UNUSED_SPACE_REQUIRED_FOR_UPDATE: u64 = 30_000_000;
 
-use std::collections::HashMap;
-
-#[derive(Debug, PartialEq)]
-struct Directory {
-    total_file_size: u64,
-    subdirectories: Vec<String>,
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiskConfig {
+    pub total_disk_space: u64,
+    pub unused_space_required: u64,
+    pub max_directory_size_to_consider: u64,
 }
 
-impl Directory {
-    pub fn new() -> Self {
+impl Default for DiskConfig {
+    fn default() -> Self {
         Self {
-            total_file_size: 0,
-            subdirectories: Vec::new(),
+            total_disk_space: TOTAL_DISK_SPACE,
+            unused_space_required: UNUSED_SPACE_REQUIRED_FOR_UPDATE,
+            max_directory_size_to_consider: MAX_DIRECTORY_SIZE_TO_CONSIDER,
         }
     }
 }
 
-fn directory_size(filesystem: &HashMap<String, Directory>, directory_name: &str) -> u64 {
-    let mut total_size: u64 = 0;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CleanupPolicy {
+    // The smallest single directory freeing enough space, as in the puzzle where
+    // the outermost directory is a candidate like any other.
+    SmallestDirectory,
+    // As few directories as possible, picked to free little more space than needed.
+    // Unlike in the puzzle, the outermost directory is never deleted: it would wipe
+    // the disk, and would always be a plan with a single directory.
+    FewestDirectories,
+}
 
-    let directory: &Directory = match filesystem.get(directory_name) {
-        Some(dir) => dir,
-        None => {
-            println!("Could not find item: {}", directory_name);
-            return 0;
+impl FromStr for CleanupPolicy {
+    type Err = String;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy {
+            "smallest-directory" => Ok(CleanupPolicy::SmallestDirectory),
+            "fewest-directories" => Ok(CleanupPolicy::FewestDirectories),
+            other => Err(format!("Unsupported cleanup policy: {}", other)),
         }
-    };
-    total_size += directory.total_file_size;
+    }
+}
+
+// An empty plan means that there is already enough unused space.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CleanupPlan {
+    pub directories: Vec<String>,
+    pub freed_space: u64,
+}
+
+impl fmt::Display for CleanupPlan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.directories.is_empty() {
+            return writeln!(f, "Nothing to delete");
+        }
+
+        for directory in &self.directories {
+            writeln!(f, "Delete {}", directory)?;
+        }
+
+        writeln!(f, "Freed space: {}", self.freed_space)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Node {
+    // Names of the entries of the directory, in listing order.
+    Dir { children: Vec<String> },
+    File { size: u64 },
+}
+
+// Entries are stored in creation order, so a directory always comes before its
+// entries. The root directory is the first entry and is its own parent.
+#[derive(Debug, PartialEq)]
+struct Entry {
+    name: String,
+    parent: usize,
+    node: Node,
+    child_ids: HashMap<String, usize>,
+}
+
+// Entries are looked up by normalized paths, an absolute form without a trailing
+// slash such as "/", "/a" or "/a/e". Sizes are cumulative for directories and are
+// computed once the filesystem is built.
+#[derive(Debug, PartialEq)]
+pub struct Filesystem {
+    entries: Vec<Entry>,
+    sizes: Vec<u64>,
+    // Numbers given to every entry when a depth-first traversal enters and leaves it.
+    tour: Vec<(usize, usize)>,
+}
 
-    match directory.subdirectories.is_empty() {
-        true => {
-            return directory.total_file_size;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TranscriptSizes {
+    // Only the files, like in the puzzle.
+    Files,
+    // Also every directory itself, listed as '.', and a hard-linked file only where
+    // du meets it first (with a size of 0 anywhere else), so that directory sizes
+    // match 'du --apparent-size -b'.
+    ApparentSize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DuOrder {
+    // Subdirectories before their parent, like du itself.
+    Traversal,
+    // Largest directories first.
+    Size,
+}
+
+// Sizes in powers of 1024 rounded up, like 'du -h' does.
+fn human_readable_size(size: u64) -> String {
+    let units = ["K", "M", "G", "T", "P", "E"];
+    let mut value = size as f64;
+
+    if size < 1024 {
+        return size.to_string();
+    }
+
+    for unit in units {
+        value /= 1024.0;
+
+        if value < 10.0 && (value * 10.0).ceil() < 100.0 {
+            return format!("{:.1}{}", (value * 10.0).ceil() / 10.0, unit);
+        } else if value.ceil() < 1024.0 {
+            return format!("{}{}", value.ceil(), unit);
         }
-        false => {
-            for subdirectory in directory.subdirectories.iter() {
-                total_size += directory_size(filesystem, subdirectory);
+    }
+
+    format!("{}E", (value * 1024.0).ceil())
+}
+
+fn normalize_path(path: &str) -> String {
+    let mut components: Vec<&str> = Vec::new();
+
+    for component in path.split('/') {
+        match component {
+            "" | "." => continue,
+            ".." => {
+                components.pop();
             }
+            other => components.push(other),
         }
     }
 
-    total_size
+    format!("/{}", components.join("/"))
 }
 
-fn build_filesystem(input: &str) -> Result<HashMap<String, Directory>, String> {
-    let mut path: Vec<&str> = Vec::new();
-    let mut filesystem: HashMap<String, Directory> = HashMap::new();
+impl Default for Filesystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-    for line in input.lines() {
-        // Idea for the command and 'match command' below taken from:
-        // https://github.com/orlp/aoc2022/blob/master/src/bin/day07.rs#L34-L46
+impl Filesystem {
+    pub fn new() -> Self {
+        Self {
+            entries: vec![Entry {
+                name: "/".to_string(),
+                parent: 0,
+                node: Node::Dir { children: vec![] },
+                child_ids: HashMap::new(),
+            }],
+            sizes: vec![0],
+            tour: vec![(0, 1)],
+        }
+    }
 
-        let mut command = Vec::with_capacity(3);
-        command.splice(.., line.split_ascii_whitespace());
+    fn find(&self, path: &str) -> Option<usize> {
+        let mut id: usize = 0;
 
-        match command[..] {
-            ["$", "cd", directory] => match directory {
-                ".." => {
-                    path.pop();
-                }
-                other => {
-                    path.push(other);
+        for name in normalize_path(path)
+            .split('/')
+            .filter(|name| !name.is_empty())
+        {
+            id = *self.entries[id].child_ids.get(name)?;
+        }
+
+        Some(id)
+    }
 
-                    filesystem
-                        .entry(path.join("/"))
-                        .or_insert_with(Directory::new);
+    fn path_of(&self, id: usize) -> String {
+        let mut names: Vec<&str> = Vec::new();
+        let mut current = id;
+
+        while current != 0 {
+            names.push(&self.entries[current].name);
+            current = self.entries[current].parent;
+        }
+
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    // Paths of all the directories indexed by id, empty for files. Every entry comes
+    // after its parent, so a single pass extends the path of the parent.
+    fn directory_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = vec![String::new(); self.entries.len()];
+
+        paths[0] = "/".to_string();
+
+        for (id, entry) in self.entries.iter().enumerate().skip(1) {
+            if let Node::Dir { .. } = entry.node {
+                paths[id] = match entry.parent {
+                    0 => format!("/{}", entry.name),
+                    parent => format!("{}/{}", paths[parent], entry.name),
+                };
+            }
+        }
+
+        paths
+    }
+
+    pub fn get(&self, path: &str) -> Option<&Node> {
+        Some(&self.entries[self.find(path)?].node)
+    }
+
+    // Listing the same directory again updates its entries instead of adding them twice.
+    fn add_entry(&mut self, directory: usize, name: &str, node: Node) -> Result<usize, String> {
+        match self.entries[directory].child_ids.get(name).copied() {
+            Some(id) => match (&mut self.entries[id].node, node) {
+                (Node::File { size }, Node::File { size: new_size }) => {
+                    *size = new_size;
+                    Ok(id)
                 }
+                (Node::Dir { .. }, Node::Dir { .. }) => Ok(id),
+                _ => Err(format!(
+                    "Conflicting file and directory: {}",
+                    self.path_of(id)
+                )),
             },
-            ["$", "ls"] => {
-                continue;
+            None => {
+                let id = self.entries.len();
+
+                match &mut self.entries[directory].node {
+                    Node::Dir { children } => children.push(name.to_string()),
+                    Node::File { .. } => {
+                        return Err(format!(
+                            "Could not find directory: {}",
+                            self.path_of(directory)
+                        ))
+                    }
+                }
+
+                self.entries[directory]
+                    .child_ids
+                    .insert(name.to_string(), id);
+                self.entries.push(Entry {
+                    name: name.to_string(),
+                    parent: directory,
+                    node,
+                    child_ids: HashMap::new(),
+                });
+
+                Ok(id)
             }
-            ["dir", directory_name] => {
-                let directory_path = path.join("/") + "/" + directory_name;
+        }
+    }
 
-                let current_directory = match filesystem.get_mut(&path.join("/")) {
-                    Some(dir) => dir,
-                    None => return Err(format!("Could not find item: {}", path.join("/"))),
-                };
-                current_directory
-                    .subdirectories
-                    .push(directory_path.clone());
+    // Follows a 'cd' argument from a directory, creating the missing directories.
+    fn change_directory(&mut self, directory: usize, path: &str) -> Result<usize, String> {
+        let mut id = match path.starts_with('/') {
+            true => 0,
+            false => directory,
+        };
+
+        for name in path.split('/') {
+            id = match name {
+                "" | "." => id,
+                ".." => self.entries[id].parent,
+                other => self.add_entry(id, other, Node::Dir { children: vec![] })?,
+            };
+        }
+
+        Ok(id)
+    }
+
+    // Single pass over the entries from the last created to the first one: every
+    // entry is complete by the time its size is added to its parent.
+    // A depth-first traversal then numbers the entries in the order it enters them:
+    // an entry is left once all the entries inside it are numbered.
+    fn compute_sizes(&mut self) {
+        let mut number_of_entries_inside: Vec<usize> = vec![1; self.entries.len()];
+        let mut stack: Vec<usize> = vec![0];
+        let mut next_number: usize = 0;
+
+        self.sizes = vec![0; self.entries.len()];
+        self.tour = vec![(0, 0); self.entries.len()];
+
+        for id in (0..self.entries.len()).rev() {
+            if let Node::File { size } = self.entries[id].node {
+                self.sizes[id] = size;
             }
-            _ => {
-                let current_directory = match filesystem.get_mut(&path.join("/")) {
-                    Some(dir) => dir,
-                    None => return Err(format!("Could not find item: {}", path.join("/"))),
+
+            if id != 0 {
+                self.sizes[self.entries[id].parent] += self.sizes[id];
+                number_of_entries_inside[self.entries[id].parent] += number_of_entries_inside[id];
+            }
+        }
+
+        while let Some(id) = stack.pop() {
+            self.tour[id] = (next_number, next_number + number_of_entries_inside[id]);
+            next_number += 1;
+
+            if let Node::Dir { children } = &self.entries[id].node {
+                stack.extend(children.iter().map(|name| self.entries[id].child_ids[name]));
+            }
+        }
+    }
+
+    pub fn size_of(&self, path: &str) -> Option<u64> {
+        Some(self.sizes[self.find(path)?])
+    }
+
+    fn subdirectories(&self, id: usize) -> Vec<usize> {
+        match &self.entries[id].node {
+            Node::Dir { children } => children
+                .iter()
+                .map(|name| self.entries[id].child_ids[name])
+                .filter(|child| matches!(self.entries[*child].node, Node::Dir { .. }))
+                .collect(),
+            Node::File { .. } => vec![],
+        }
+    }
+
+    // Directories in depth-first order, every directory coming after its
+    // subdirectories. An explicit stack keeps deep trees from overflowing.
+    fn directory_ids_post_order(&self) -> Vec<usize> {
+        let mut directories: Vec<usize> = Vec::new();
+        let mut stack: Vec<(usize, bool)> = vec![(0, false)];
+
+        while let Some((id, expanded)) = stack.pop() {
+            if expanded {
+                directories.push(id);
+            } else {
+                stack.push((id, true));
+                stack.extend(
+                    self.subdirectories(id)
+                        .into_iter()
+                        .rev()
+                        .map(|subdirectory| (subdirectory, false)),
+                );
+            }
+        }
+
+        directories
+    }
+
+    pub fn directories_post_order(&self) -> Vec<String> {
+        let mut paths = self.directory_paths();
+
+        self.directory_ids_post_order()
+            .into_iter()
+            .map(|id| std::mem::take(&mut paths[id]))
+            .collect()
+    }
+
+    // Same nested format as the puzzle description, entries in listing order.
+    pub fn render_tree(&self) -> String {
+        let mut output = String::new();
+        let mut stack: Vec<(usize, usize)> = vec![(0, 0)];
+
+        while let Some((id, depth)) = stack.pop() {
+            let entry = &self.entries[id];
+
+            match &entry.node {
+                Node::Dir { children } => {
+                    output += &format!("{}- {} (dir)\n", "  ".repeat(depth), entry.name);
+                    stack.extend(
+                        children
+                            .iter()
+                            .rev()
+                            .map(|child| (entry.child_ids[child], depth + 1)),
+                    );
+                }
+                Node::File { size } => {
+                    output += &format!(
+                        "{}- {} (file, size={})\n",
+                        "  ".repeat(depth),
+                        entry.name,
+                        size
+                    );
+                }
+            }
+        }
+
+        output
+    }
+
+    pub fn render_du(&self, order: DuOrder, human_readable: bool) -> String {
+        let mut directories = self.directory_ids_post_order();
+        let paths = self.directory_paths();
+
+        if order == DuOrder::Size {
+            directories.sort_by_key(|id| Reverse(self.sizes[*id]));
+        }
+
+        directories
+            .iter()
+            .map(|id| match human_readable {
+                true => format!("{}\t{}\n", human_readable_size(self.sizes[*id]), paths[*id]),
+                false => format!("{}\t{}\n", self.sizes[*id], paths[*id]),
+            })
+            .collect()
+    }
+
+    pub fn directories(&self) -> impl Iterator<Item = String> + '_ {
+        let mut paths = self.directory_paths();
+
+        self.directory_ids()
+            .map(move |id| std::mem::take(&mut paths[id]))
+    }
+
+    fn directory_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(id, entry)| match entry.node {
+                Node::Dir { .. } => Some(id),
+                Node::File { .. } => None,
+            })
+    }
+
+    pub fn directory_sizes(&self) -> impl Iterator<Item = u64> + '_ {
+        self.directory_ids().map(|id| self.sizes[id])
+    }
+
+    pub fn space_to_free(&self, config: &DiskConfig) -> u64 {
+        let unused_space = config.total_disk_space.saturating_sub(self.sizes[0]);
+
+        config.unused_space_required.saturating_sub(unused_space)
+    }
+
+    // Whether a directory is one of the plan, or is inside or contains one of them.
+    // The directories of a plan are keyed by the number the traversal enters them
+    // with, and none of them contains another.
+    fn is_nested_in_plan(&self, plan: &BTreeMap<usize, usize>, id: usize) -> bool {
+        let (enter, leave) = self.tour[id];
+
+        plan.range(..=enter)
+            .next_back()
+            .is_some_and(|(_, other_id)| self.tour[*other_id].1 > enter)
+            || plan.range(enter..leave).next().is_some()
+    }
+
+    // The largest directories, none inside another, give the fewest directories
+    // that can free enough space: no other directories can free more, as a directory
+    // frees at least as much as the directories inside it. Then every directory of
+    // the plan, from the largest, is replaced with the smallest one still freeing
+    // enough space, so that little more space than needed is freed.
+    fn plan_fewest_directories(&self, space_to_free: u64) -> Option<Vec<usize>> {
+        let mut candidates: Vec<usize> = self.directory_ids().filter(|id| *id != 0).collect();
+        let top_level_space: u64 = self
+            .subdirectories(0)
+            .iter()
+            .map(|id| self.sizes[*id])
+            .sum();
+        let mut plan: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut freed_space: u64 = 0;
+
+        if space_to_free > top_level_space {
+            return None;
+        }
+
+        candidates.sort_by_key(|id| Reverse(self.sizes[*id]));
+
+        for candidate in &candidates {
+            if freed_space >= space_to_free {
+                break;
+            }
+
+            if !self.is_nested_in_plan(&plan, *candidate) {
+                plan.insert(self.tour[*candidate].0, *candidate);
+                freed_space += self.sizes[*candidate];
+            }
+        }
+
+        let mut directories: Vec<usize> = plan.values().copied().collect();
+
+        candidates.reverse();
+        directories.sort_by_key(|id| Reverse(self.sizes[*id]));
+
+        for directory in directories.iter_mut() {
+            let smallest_size = self.sizes[*directory] - (freed_space - space_to_free);
+            let first_candidate = candidates.partition_point(|id| self.sizes[*id] < smallest_size);
+
+            plan.remove(&self.tour[*directory].0);
+
+            // The directory itself is the last possible replacement.
+            let replacement = candidates[first_candidate..]
+                .iter()
+                .find(|id| !self.is_nested_in_plan(&plan, **id))
+                .copied()
+                .unwrap_or(*directory);
+
+            plan.insert(self.tour[replacement].0, replacement);
+            freed_space = freed_space - self.sizes[*directory] + self.sizes[replacement];
+            *directory = replacement;
+        }
+
+        directories.sort_by_key(|id| Reverse(self.sizes[*id]));
+        Some(directories)
+    }
+
+    pub fn plan_cleanup(
+        &self,
+        space_to_free: u64,
+        policy: CleanupPolicy,
+    ) -> Result<CleanupPlan, String> {
+        if space_to_free == 0 {
+            return Ok(CleanupPlan {
+                directories: vec![],
+                freed_space: 0,
+            });
+        }
+
+        let directories = match policy {
+            CleanupPolicy::SmallestDirectory => self
+                .directory_ids()
+                .filter(|id| self.sizes[*id] >= space_to_free)
+                .min_by_key(|id| self.sizes[*id])
+                .map(|id| vec![id]),
+            CleanupPolicy::FewestDirectories => self.plan_fewest_directories(space_to_free),
+        };
+
+        match directories {
+            Some(directories) => Ok(CleanupPlan {
+                directories: directories.iter().map(|id| self.path_of(*id)).collect(),
+                freed_space: directories.iter().map(|id| self.sizes[*id]).sum(),
+            }),
+            None => Err(format!(
+                "Could not free {} of disk space by deleting directories",
+                space_to_free
+            )),
+        }
+    }
+}
+
+// First field of a line and the rest of it. Fields are separated by any amount of
+// whitespace, but the rest is kept whole as names may contain spaces.
+fn split_field(line: &str) -> (&str, &str) {
+    let line = line.trim_matches(|c: char| c.is_ascii_whitespace());
+
+    match line.split_once(|c: char| c.is_ascii_whitespace()) {
+        Some((field, rest)) => (
+            field,
+            rest.trim_start_matches(|c: char| c.is_ascii_whitespace()),
+        ),
+        None => (line, ""),
+    }
+}
+
+pub fn build_filesystem(input: &str) -> Result<Filesystem, String> {
+    let mut current_directory: Option<usize> = None;
+    let mut filesystem = Filesystem::new();
+
+    for line in input.lines() {
+        // Idea for the command and 'match command' below taken from:
+        // https://github.com/orlp/aoc2022/blob/master/src/bin/day07.rs#L34-L46
+        let (first_field, rest) = split_field(line);
+
+        match (first_field, split_field(rest)) {
+            ("$", ("cd", directory)) if !directory.is_empty() => {
+                current_directory =
+                    Some(filesystem.change_directory(current_directory.unwrap_or(0), directory)?);
+            }
+            ("$", ("ls", "")) => {
+                continue;
+            }
+            ("dir", _) if !rest.is_empty() => match current_directory {
+                Some(current) => {
+                    filesystem.add_entry(current, rest, Node::Dir { children: vec![] })?;
+                }
+                None => return Err(format!("No current directory for line: {}", line)),
+            },
+            (file_size, _) => {
+                let current = match current_directory {
+                    Some(current) => current,
+                    None => return Err(format!("No current directory for line: {}", line)),
                 };
 
-                match line.split(' ').collect::<Vec<&str>>()[0].parse::<u64>() {
-                    Ok(file_size) => current_directory.total_file_size += file_size,
+                match file_size.parse::<u64>() {
+                    Ok(size) => {
+                        filesystem.add_entry(current, rest, Node::File { size })?;
+                    }
                     Err(err) => println!(
                         "Could not parse file size: {}. Ignoring line: {}",
                         err, line
@@ -202,64 +702,178 @@ fn build_filesystem(input: &str) -> Result<HashMap<String, Directory>, String> {
         }
     }
 
+    filesystem.compute_sizes();
+
     Ok(filesystem)
 }
 
-pub fn part1(input: &str) -> Result<String, String> {
-    let mut total_size: u64 = 0;
+// Device and inode of the files with several hard links, which du only counts
+// the first time it meets them.
+#[cfg(unix)]
+fn hard_link_id(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
 
-    let filesystem = match build_filesystem(input) {
-        Ok(filesystem) => filesystem,
-        Err(err) => return Err(err),
-    };
+    match !metadata.is_dir() && metadata.nlink() > 1 {
+        true => Some((metadata.dev(), metadata.ino())),
+        false => None,
+    }
+}
+
+#[cfg(not(unix))]
+fn hard_link_id(_metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+// Lines listed by 'ls' in a directory, sorted by name, with the listings of its subdirectories.
+struct Listing {
+    lines: Vec<(String, String)>,
+    subdirectories: Vec<(String, Listing)>,
+}
 
-    for directory in filesystem.keys() {
-        let size = directory_size(&filesystem, directory);
+impl Listing {
+    fn write(&self, transcript: &mut String) {
+        *transcript += "$ ls\n";
 
-        if size <= MAX_DIRECTORY_SIZE_TO_CONSIDER {
-            total_size += size;
+        for (_, line) in &self.lines {
+            *transcript += line;
         }
-    }
 
-    Ok(total_size.to_string())
+        for (name, listing) in &self.subdirectories {
+            *transcript += &format!("$ cd {}\n", name);
+            listing.write(transcript);
+            *transcript += "$ cd ..\n";
+        }
+    }
 }
 
-pub fn part2(input: &str) -> Result<String, String> {
-    let mut sizes_of_candidate_directories_to_delete: Vec<u64> = Vec::new();
-
-    let filesystem = match build_filesystem(input) {
-        Ok(filesystem) => filesystem,
-        Err(err) => return Err(err),
+// Entries are visited in the order the system lists them, going down into every
+// subdirectory as soon as it is met, like du does.
+fn list_directory(
+    directory: &Path,
+    sizes: TranscriptSizes,
+    hard_links: &mut HashSet<(u64, u64)>,
+) -> Result<Listing, String> {
+    let read_error = |err: std::io::Error| {
+        format!(
+            "Could not read directory '{}'. {}",
+            directory.display(),
+            err
+        )
+    };
+    let mut listing = Listing {
+        lines: vec![],
+        subdirectories: vec![],
     };
 
-    let unused_space = TOTAL_DISK_SPACE - directory_size(&filesystem, "/");
-
-    if unused_space > UNUSED_SPACE_REQUIRED_FOR_UPDATE {
-        Err("Problem with the filesystem disk space! The expectation is to have just enough free space to the upgrade or less".to_string())
-    } else {
-        let additonal_space_required_for_update = UNUSED_SPACE_REQUIRED_FOR_UPDATE - unused_space;
+    if sizes == TranscriptSizes::ApparentSize {
+        let metadata = fs::symlink_metadata(directory).map_err(read_error)?;
 
-        for directory in filesystem.keys() {
-            let size = directory_size(&filesystem, directory);
+        listing
+            .lines
+            .push((".".to_string(), format!("{} .\n", metadata.len())));
+    }
 
-            if size >= additonal_space_required_for_update {
-                sizes_of_candidate_directories_to_delete.push(size);
+    for entry in fs::read_dir(directory).map_err(read_error)? {
+        // Symbolic links are not followed, they are listed with their own size like du does.
+        let (name, metadata) = entry
+            .and_then(|entry| Ok((entry.file_name(), entry.metadata()?)))
+            .map_err(read_error)?;
+
+        // Names are read back with the whitespace around them trimmed.
+        let name = match name.to_str() {
+            Some(name)
+                if !name.contains('\n')
+                    && name.trim_matches(|c: char| c.is_ascii_whitespace()) == name =>
+            {
+                name.to_string()
             }
+            _ => {
+                return Err(format!(
+                    "Unsupported file name: {}",
+                    directory.join(name).display()
+                ))
+            }
+        };
+
+        if metadata.is_dir() {
+            let subdirectory = list_directory(&directory.join(&name), sizes, hard_links)?;
+
+            listing
+                .lines
+                .push((name.clone(), format!("dir {}\n", name)));
+            listing.subdirectories.push((name, subdirectory));
+            continue;
         }
 
-        sizes_of_candidate_directories_to_delete.sort_by(|a, b| b.cmp(a));
+        let size = match (sizes, hard_link_id(&metadata)) {
+            (TranscriptSizes::ApparentSize, Some(id)) if !hard_links.insert(id) => 0,
+            _ => metadata.len(),
+        };
 
-        Ok(sizes_of_candidate_directories_to_delete
-            .pop()
-            .unwrap()
-            .to_string())
+        listing
+            .lines
+            .push((name.clone(), format!("{} {}\n", size, name)));
     }
+
+    listing.lines.sort();
+    listing.subdirectories.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Ok(listing)
+}
+
+// Terminal output exploring a local directory in the format of the puzzle input.
+pub fn generate_transcript(directory: &Path, sizes: TranscriptSizes) -> Result<String, String> {
+    let mut transcript = "$ cd /\n".to_string();
+
+    list_directory(directory, sizes, &mut HashSet::new())?.write(&mut transcript);
+
+    Ok(transcript)
+}
+
+pub fn part1_with_config(input: &str, config: &DiskConfig) -> Result<String, String> {
+    let filesystem = build_filesystem(input)?;
+
+    let total_size: u64 = filesystem
+        .directory_sizes()
+        .filter(|size| *size <= config.max_directory_size_to_consider)
+        .sum();
+
+    Ok(total_size.to_string())
+}
+
+pub fn part1(input: &str) -> Result<String, String> {
+    part1_with_config(input, &DiskConfig::default())
+}
+
+pub fn plan_cleanup(
+    input: &str,
+    config: &DiskConfig,
+    policy: CleanupPolicy,
+) -> Result<CleanupPlan, String> {
+    let filesystem = build_filesystem(input)?;
+
+    filesystem.plan_cleanup(filesystem.space_to_free(config), policy)
+}
+
+pub fn part2_with_config(input: &str, config: &DiskConfig) -> Result<String, String> {
+    let plan = plan_cleanup(input, config, CleanupPolicy::SmallestDirectory)?;
+
+    Ok(plan.freed_space.to_string())
+}
+
+pub fn part2(input: &str) -> Result<String, String> {
+    part2_with_config(input, &DiskConfig::default())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day7::{build_filesystem, directory_size, part1, part2, Directory};
-    use std::collections::HashMap;
+    use crate::day7::{
+        build_filesystem, generate_transcript, human_readable_size, normalize_path, part1,
+        part1_with_config, part2, part2_with_config, plan_cleanup, CleanupPlan, CleanupPolicy,
+        DiskConfig, DuOrder, Filesystem, Node, TranscriptSizes,
+    };
+    use std::fs;
+    use std::path::Path;
 
     static TEST_INPUT: &str = "$ cd /
 $ ls
@@ -302,48 +916,126 @@ bad_file_size b.txt
     static TEST_INPUT_EMPTY_FILESYSTEM: &str = "$ ls
 ";
 
+    static TEST_INPUT_REPEATED_LS_AND_CD_ROOT: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+$ cd a
+$ ls
+29116 f
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+$ cd /a
+$ ls
+29116 f
+$ cd a/../../a/./
+$ ls
+29116 f
+";
+
+    static TEST_TREE_OUTPUT: &str = "- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - j (file, size=4060174)
+    - d.log (file, size=8033020)
+    - d.ext (file, size=5626152)
+    - k (file, size=7214296)
+";
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("/"), "/");
+        assert_eq!(normalize_path(""), "/");
+        assert_eq!(normalize_path("//a/e/"), "/a/e");
+        assert_eq!(normalize_path("a/./e/../d"), "/a/d");
+        assert_eq!(normalize_path("/.."), "/");
+    }
+
     #[test]
     fn test_build_filesystem() {
+        let filesystem = build_filesystem(TEST_INPUT).unwrap();
+
         assert_eq!(
-            build_filesystem(TEST_INPUT).unwrap(),
-            HashMap::from([
-                (
-                    "/".to_string(),
-                    Directory {
-                        total_file_size: 23352670,
-                        subdirectories: vec!["//a".to_string(), "//d".to_string()]
-                    }
-                ),
-                (
-                    "//a".to_string(),
-                    Directory {
-                        total_file_size: 94269,
-                        subdirectories: vec!["//a/e".to_string()]
-                    }
-                ),
-                (
-                    "//a/e".to_string(),
-                    Directory {
-                        total_file_size: 584,
-                        subdirectories: vec![]
-                    }
-                ),
-                (
-                    "//d".to_string(),
-                    Directory {
-                        total_file_size: 24933642,
-                        subdirectories: vec![]
-                    }
-                )
-            ])
-        )
+            filesystem.get("/"),
+            Some(&Node::Dir {
+                children: vec![
+                    "a".to_string(),
+                    "b.txt".to_string(),
+                    "c.dat".to_string(),
+                    "d".to_string()
+                ]
+            })
+        );
+        assert_eq!(
+            filesystem.get("/a"),
+            Some(&Node::Dir {
+                children: vec![
+                    "e".to_string(),
+                    "f".to_string(),
+                    "g".to_string(),
+                    "h.lst".to_string()
+                ]
+            })
+        );
+        assert_eq!(filesystem.get("/a/e/i"), Some(&Node::File { size: 584 }));
+        assert_eq!(
+            filesystem.get("/d/d.log"),
+            Some(&Node::File { size: 8033020 })
+        );
+
+        let mut directories: Vec<String> = filesystem.directories().collect();
+        directories.sort();
+
+        assert_eq!(directories, vec!["/", "/a", "/a/e", "/d"]);
+    }
+
+    #[test]
+    fn test_build_filesystem_with_repeated_ls_and_cd_root() {
+        let filesystem = build_filesystem(TEST_INPUT_REPEATED_LS_AND_CD_ROOT).unwrap();
+
+        assert_eq!(
+            filesystem.get("/"),
+            Some(&Node::Dir {
+                children: vec!["a".to_string(), "b.txt".to_string()]
+            })
+        );
+        assert_eq!(filesystem.size_of("/a"), Some(29116));
+        assert_eq!(filesystem.size_of("/"), Some(14848514 + 29116));
+    }
+
+    #[test]
+    fn test_build_filesystem_with_tabs_and_repeated_spaces() {
+        let filesystem =
+            build_filesystem("$  cd /\n$\tls\ndir  my dir\n12\ta\n$ cd   my dir \n$ ls\n3  b c\n")
+                .unwrap();
+
+        assert_eq!(filesystem.get("/a"), Some(&Node::File { size: 12 }));
+        assert_eq!(filesystem.get("/my dir/b c"), Some(&Node::File { size: 3 }));
+        assert_eq!(filesystem.size_of("/"), Some(15));
+    }
+
+    #[test]
+    fn test_build_filesystem_with_conflicting_entries() {
+        assert_eq!(
+            build_filesystem("$ cd /\n$ ls\n123 a\ndir a\n"),
+            Err("Conflicting file and directory: /a".to_string())
+        );
     }
 
     #[test]
     fn test_build_filesystem_with_dir_command_and_no_current_directory() {
         assert_eq!(
             build_filesystem(TEST_INPUT_DIR_COMMAND_AND_NO_CURRENT_DIRECTORY),
-            Err("Could not find item: ".to_string())
+            Err("No current directory for line: dir missing_previous_cd".to_string())
         );
     }
 
@@ -351,52 +1043,65 @@ bad_file_size b.txt
     fn test_build_filesystem_with_filesize_and_filename_and_no_current_directory() {
         assert_eq!(
             build_filesystem(TEST_INPUT_FILESIZE_AND_FILENAME_AND_NO_CURRENT_DIRECTORY),
-            Err("Could not find item: ".to_string())
+            Err("No current directory for line: 1234 test.txt".to_string())
         );
     }
 
     #[test]
     fn test_build_filesystem_with_bad_filesize() {
+        let filesystem = build_filesystem(TEST_INPUT_BAD_FILESIZE).unwrap();
+
         assert_eq!(
-            build_filesystem(TEST_INPUT_BAD_FILESIZE).unwrap(),
-            HashMap::from([(
-                "/".to_string(),
-                Directory {
-                    total_file_size: 8504156,
-                    subdirectories: vec!["//a".to_string()]
-                }
-            )])
+            filesystem.get("/"),
+            Some(&Node::Dir {
+                children: vec!["a".to_string(), "c.dat".to_string()]
+            })
+        );
+        assert_eq!(filesystem.size_of("/"), Some(8504156));
+    }
+
+    #[test]
+    fn test_build_filesystem_with_empty_filesystem() {
+        assert_eq!(
+            build_filesystem(TEST_INPUT_EMPTY_FILESYSTEM),
+            Ok(Filesystem::new())
         );
     }
 
     #[test]
     fn test_directory_size_e() {
         let filesystem = build_filesystem(TEST_INPUT).unwrap();
-        assert_eq!(directory_size(&filesystem, "//a/e"), 584);
+        assert_eq!(filesystem.size_of("/a/e"), Some(584));
     }
 
     #[test]
     fn test_directory_size_d() {
         let filesystem = build_filesystem(TEST_INPUT).unwrap();
-        assert_eq!(directory_size(&filesystem, "//d"), 24933642);
+        assert_eq!(filesystem.size_of("/d"), Some(24933642));
     }
 
     #[test]
     fn test_directory_size_a() {
         let filesystem = build_filesystem(TEST_INPUT).unwrap();
-        assert_eq!(directory_size(&filesystem, "//a"), 94853);
+        assert_eq!(filesystem.size_of("/a"), Some(94853));
     }
 
     #[test]
     fn test_directory_size_root() {
         let filesystem = build_filesystem(TEST_INPUT).unwrap();
-        assert_eq!(directory_size(&filesystem, "/"), 48381165);
+        assert_eq!(filesystem.size_of("/"), Some(48381165));
     }
 
     #[test]
     fn test_directory_size_non_existing_dir() {
         let filesystem = build_filesystem(TEST_INPUT).unwrap();
-        assert_eq!(directory_size(&filesystem, "//test"), 0);
+        assert_eq!(filesystem.size_of("/test"), None);
+    }
+
+    #[test]
+    fn test_file_size() {
+        let filesystem = build_filesystem(TEST_INPUT).unwrap();
+        assert_eq!(filesystem.size_of("/a/h.lst"), Some(62596));
     }
 
     #[test]
@@ -408,7 +1113,7 @@ bad_file_size b.txt
     fn test_part1_bad_input() {
         assert_eq!(
             part1(TEST_INPUT_DIR_COMMAND_AND_NO_CURRENT_DIRECTORY),
-            Err("Could not find item: ".to_string())
+            Err("No current directory for line: dir missing_previous_cd".to_string())
         );
     }
 
@@ -421,15 +1126,340 @@ bad_file_size b.txt
     fn test_part2_bad_input() {
         assert_eq!(
             part2(TEST_INPUT_DIR_COMMAND_AND_NO_CURRENT_DIRECTORY),
-            Err("Could not find item: ".to_string())
+            Err("No current directory for line: dir missing_previous_cd".to_string())
         );
     }
 
     #[test]
     fn test_part2_empty_filesystem() {
+        assert_eq!(part2(TEST_INPUT_EMPTY_FILESYSTEM), Ok(0.to_string()));
+    }
+
+    #[test]
+    fn test_part1_and_part2_with_config() {
+        let config = DiskConfig {
+            total_disk_space: 50_000_000,
+            unused_space_required: 10_000_000,
+            max_directory_size_to_consider: 1_000,
+        };
+
+        assert_eq!(part1_with_config(TEST_INPUT, &config), Ok(584.to_string()));
+        assert_eq!(
+            part2_with_config(TEST_INPUT, &config),
+            Ok(24933642.to_string())
+        );
+    }
+
+    #[test]
+    fn test_part2_with_not_enough_disk_space() {
+        let config = DiskConfig {
+            total_disk_space: 1_000,
+            unused_space_required: 50_000_000,
+            ..DiskConfig::default()
+        };
+
+        assert_eq!(
+            part2_with_config(TEST_INPUT, &config),
+            Err("Could not free 50000000 of disk space by deleting directories".to_string())
+        );
+    }
+
+    #[test]
+    fn test_plan_cleanup() {
+        let config = DiskConfig {
+            unused_space_required: 46_647_330,
+            ..DiskConfig::default()
+        };
+
+        assert_eq!(
+            plan_cleanup(
+                TEST_INPUT,
+                &DiskConfig::default(),
+                CleanupPolicy::FewestDirectories
+            ),
+            Ok(CleanupPlan {
+                directories: vec!["/d".to_string()],
+                freed_space: 24933642
+            })
+        );
+        assert_eq!(
+            plan_cleanup(TEST_INPUT, &config, CleanupPolicy::SmallestDirectory),
+            Ok(CleanupPlan {
+                directories: vec!["/".to_string()],
+                freed_space: 48381165
+            })
+        );
+        assert_eq!(
+            plan_cleanup(TEST_INPUT, &config, CleanupPolicy::FewestDirectories),
+            Ok(CleanupPlan {
+                directories: vec!["/d".to_string(), "/a".to_string()],
+                freed_space: 25028495
+            })
+        );
+        assert_eq!(
+            plan_cleanup(
+                TEST_INPUT,
+                &DiskConfig {
+                    unused_space_required: 46_647_331,
+                    ..DiskConfig::default()
+                },
+                CleanupPolicy::FewestDirectories
+            ),
+            Err("Could not free 25028496 of disk space by deleting directories".to_string())
+        );
+    }
+
+    #[test]
+    fn test_plan_cleanup_picks_smallest_freed_space() {
+        let filesystem = build_filesystem(
+            "$ cd /\n$ ls\ndir a\ndir b\ndir c\n$ cd a\n$ ls\n60 x\n$ cd ../b\n$ ls\n50 x\n$ cd ../c\n$ ls\n45 x",
+        )
+        .unwrap();
+
         assert_eq!(
-            part2(TEST_INPUT_EMPTY_FILESYSTEM),
-            Err("Problem with the filesystem disk space! The expectation is to have just enough free space to the upgrade or less".to_string())
+            filesystem.plan_cleanup(95, CleanupPolicy::FewestDirectories),
+            Ok(CleanupPlan {
+                directories: vec!["/b".to_string(), "/c".to_string()],
+                freed_space: 95
+            })
+        );
+        assert_eq!(
+            filesystem
+                .plan_cleanup(0, CleanupPolicy::FewestDirectories)
+                .unwrap()
+                .to_string(),
+            "Nothing to delete\n"
+        );
+        assert_eq!(
+            filesystem
+                .plan_cleanup(100, CleanupPolicy::FewestDirectories)
+                .unwrap()
+                .to_string(),
+            "Delete /a\nDelete /c\nFreed space: 105\n"
+        );
+        assert_eq!(
+            "fewest-directories".parse::<CleanupPolicy>(),
+            Ok(CleanupPolicy::FewestDirectories)
+        );
+    }
+
+    #[test]
+    fn test_plan_cleanup_with_many_directories() {
+        let mut input = "$ cd /\n$ ls\n1000 x\n".to_string();
+
+        for index in 0..40 {
+            input += &format!("dir {}\n", index);
+        }
+
+        for index in 0..40 {
+            input += &format!(
+                "$ cd /{}\n$ ls\n{} x\ndir sub\n$ cd sub\n$ ls\n{} y\n",
+                index,
+                100 + index,
+                1000 + index
+            );
+        }
+
+        let filesystem = build_filesystem(&input).unwrap();
+        let top_level_space: u64 = (0..40).map(|index| 1100 + 2 * index).sum();
+
+        assert_eq!(
+            filesystem.plan_cleanup(top_level_space + 500, CleanupPolicy::FewestDirectories),
+            Err(format!(
+                "Could not free {} of disk space by deleting directories",
+                top_level_space + 500
+            ))
+        );
+        assert_eq!(
+            filesystem
+                .plan_cleanup(top_level_space + 500, CleanupPolicy::SmallestDirectory)
+                .map(|plan| plan.directories),
+            Ok(vec!["/".to_string()])
+        );
+
+        let plan = filesystem
+            .plan_cleanup(top_level_space - 100, CleanupPolicy::FewestDirectories)
+            .unwrap();
+
+        assert_eq!(plan.directories.len(), 40);
+        assert_eq!(plan.freed_space, top_level_space - 100);
+        assert!(plan.directories.contains(&"/0/sub".to_string()));
+    }
+
+    #[test]
+    fn test_directories_post_order() {
+        let filesystem = build_filesystem(TEST_INPUT).unwrap();
+
+        assert_eq!(
+            filesystem.directories_post_order(),
+            vec!["/a/e", "/a", "/d", "/"]
+        );
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let filesystem = build_filesystem(TEST_INPUT).unwrap();
+
+        assert_eq!(filesystem.render_tree(), TEST_TREE_OUTPUT);
+        assert_eq!(Filesystem::new().render_tree(), "- / (dir)\n");
+    }
+
+    #[test]
+    fn test_render_du() {
+        let filesystem = build_filesystem(TEST_INPUT).unwrap();
+
+        assert_eq!(
+            filesystem.render_du(DuOrder::Traversal, false),
+            "584\t/a/e\n94853\t/a\n24933642\t/d\n48381165\t/\n"
+        );
+        assert_eq!(
+            filesystem.render_du(DuOrder::Size, false),
+            "48381165\t/\n24933642\t/d\n94853\t/a\n584\t/a/e\n"
+        );
+        assert_eq!(
+            filesystem.render_du(DuOrder::Size, true),
+            "47M\t/\n24M\t/d\n93K\t/a\n584\t/a/e\n"
+        );
+    }
+
+    #[test]
+    fn test_human_readable_size() {
+        assert_eq!(human_readable_size(0), "0");
+        assert_eq!(human_readable_size(1023), "1023");
+        assert_eq!(human_readable_size(1024), "1.0K");
+        assert_eq!(human_readable_size(1025), "1.1K");
+        assert_eq!(human_readable_size(10 * 1024 - 1), "10K");
+        assert_eq!(human_readable_size(1024 * 1024 - 1), "1.0M");
+        assert_eq!(human_readable_size(5 * 1024 * 1024 * 1024), "5.0G");
+    }
+
+    #[test]
+    fn test_build_filesystem_with_deeply_nested_directories() {
+        let depth = 100_000;
+        let mut input = "$ cd /\n".to_string();
+
+        for _ in 0..depth {
+            input += "$ ls\ndir a\n1 f\n$ cd a\n";
+        }
+
+        input += "$ cd /\n";
+
+        let filesystem = build_filesystem(&input).unwrap();
+
+        assert_eq!(filesystem.size_of("/"), Some(depth));
+        assert_eq!(filesystem.size_of("/a/a/a"), Some(depth - 3));
+        assert_eq!(filesystem.directory_sizes().count() as u64, depth + 1);
+        assert_eq!(part1(&input), Ok(((0..=depth).sum::<u64>()).to_string()));
+    }
+
+    #[test]
+    fn test_generate_transcript() {
+        let directory = std::env::temp_dir().join(format!("aoc-day7-{}", std::process::id()));
+
+        fs::create_dir_all(directory.join("a/e")).unwrap();
+        fs::create_dir_all(directory.join("my dir")).unwrap();
+        fs::write(directory.join("b.txt"), "12345").unwrap();
+        fs::write(directory.join("a/f"), "123").unwrap();
+        fs::write(directory.join("a/e/i"), "1").unwrap();
+        fs::write(directory.join("my dir/my file"), "12").unwrap();
+
+        let transcript = generate_transcript(&directory, TranscriptSizes::Files);
+
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            transcript,
+            Ok("$ cd /
+$ ls
+dir a
+5 b.txt
+dir my dir
+$ cd a
+$ ls
+dir e
+3 f
+$ cd e
+$ ls
+1 i
+$ cd ..
+$ cd ..
+$ cd my dir
+$ ls
+2 my file
+$ cd ..
+"
+            .to_string())
+        );
+
+        let filesystem = build_filesystem(&transcript.unwrap()).unwrap();
+
+        assert_eq!(filesystem.size_of("/"), Some(11));
+        assert_eq!(filesystem.size_of("/a"), Some(4));
+        assert_eq!(filesystem.size_of("/my dir"), Some(2));
+        assert_eq!(
+            filesystem.get("/my dir/my file"),
+            Some(&Node::File { size: 2 })
+        );
+    }
+
+    #[test]
+    fn test_generate_transcript_with_apparent_size() {
+        let directory =
+            std::env::temp_dir().join(format!("aoc-day7-apparent-{}", std::process::id()));
+
+        fs::create_dir_all(directory.join("a/e")).unwrap();
+        fs::write(directory.join("b.txt"), "12345").unwrap();
+        fs::hard_link(directory.join("b.txt"), directory.join("c.txt")).unwrap();
+        fs::write(directory.join("a/f"), "123").unwrap();
+        fs::hard_link(directory.join("a/f"), directory.join("a/e/g")).unwrap();
+        fs::write(directory.join("a/e/i"), "1").unwrap();
+
+        let own_size = |path: &str| fs::symlink_metadata(directory.join(path)).unwrap().len();
+        let sizes = [own_size(""), own_size("a"), own_size("a/e")];
+        let transcript = generate_transcript(&directory, TranscriptSizes::ApparentSize);
+
+        fs::remove_dir_all(&directory).unwrap();
+
+        let transcript = transcript.unwrap();
+        let filesystem = build_filesystem(&transcript).unwrap();
+
+        assert!(transcript.starts_with(&format!("$ cd /\n$ ls\n{} .\ndir a\n", sizes[0])));
+        // Hard links count once, whichever of them du meets first.
+        assert_eq!(filesystem.size_of("/a"), Some(sizes[1] + sizes[2] + 3 + 1));
+        assert_eq!(
+            filesystem.size_of("/"),
+            Some(sizes.iter().sum::<u64>() + 5 + 3 + 1)
+        );
+        assert!([Some(sizes[2] + 1), Some(sizes[2] + 4)].contains(&filesystem.size_of("/a/e")));
+    }
+
+    #[test]
+    fn test_generate_transcript_with_unsupported_name() {
+        let directory =
+            std::env::temp_dir().join(format!("aoc-day7-spaces-{}", std::process::id()));
+
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join(" leading space"), "1").unwrap();
+
+        let transcript = generate_transcript(&directory, TranscriptSizes::Files);
+
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            transcript,
+            Err(format!(
+                "Unsupported file name: {}",
+                directory.join(" leading space").display()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_generate_transcript_with_missing_directory() {
+        assert!(
+            generate_transcript(Path::new("/non/existing/directory"), TranscriptSizes::Files)
+                .unwrap_err()
+                .starts_with("Could not read directory '/non/existing/directory'.")
         );
     }
 }
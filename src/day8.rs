@@ -72,7 +72,126 @@ This tree's scenic score is 8 (2 * 2 * 1 * 2); this is the ideal spot for the tr
 Consider each tree on your map. What is the highest scenic score possible for any tree?
 */
 
-struct TreeGrid {
+use crate::image::{self, ImageFormat};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TreeMap {
+    Visibility,
+    ScenicScore,
+}
+
+impl FromStr for TreeMap {
+    type Err = String;
+
+    fn from_str(tree_map: &str) -> Result<Self, Self::Err> {
+        match tree_map {
+            "visibility" => Ok(TreeMap::Visibility),
+            "scenic-score" => Ok(TreeMap::ScenicScore),
+            other => Err(format!("Unsupported tree map: {}", other)),
+        }
+    }
+}
+
+// Maps a value between 0 and 1 on a dark blue, red and yellow color ramp.
+fn heat_color(intensity: f64) -> [u8; 3] {
+    let intensity = intensity.clamp(0.0, 1.0);
+
+    if intensity < 0.5 {
+        let ratio = intensity * 2.0;
+        [(255.0 * ratio) as u8, 0, (128.0 * (1.0 - ratio)) as u8]
+    } else {
+        [255, (255.0 * (intensity - 0.5) * 2.0) as u8, 0]
+    }
+}
+
+// Visibility of every tree of a line from the side of its first tree: only trees
+// taller than all the previous ones can be seen.
+fn line_visibility(heights: &[u32]) -> Vec<bool> {
+    let mut tallest_tree: Option<u32> = None;
+
+    heights
+        .iter()
+        .map(|height| match tallest_tree {
+            Some(tallest) if tallest >= *height => false,
+            _ => {
+                tallest_tree = Some(*height);
+                true
+            }
+        })
+        .collect()
+}
+
+// Viewing distance of every tree of a line towards its first tree. The stack keeps
+// the positions of trees that are not hidden behind a taller or same height tree
+// closer to the current one, so every position is pushed and popped only once.
+fn line_viewing_distances(heights: &[u32]) -> Vec<u64> {
+    let mut stack: Vec<usize> = Vec::new();
+    let mut viewing_distances: Vec<u64> = Vec::with_capacity(heights.len());
+
+    for (position, height) in heights.iter().enumerate() {
+        while let Some(previous) = stack.last() {
+            if heights[*previous] < *height {
+                stack.pop();
+            } else {
+                break;
+            }
+        }
+
+        viewing_distances.push(match stack.last() {
+            Some(blocking_tree) => (position - blocking_tree) as u64,
+            None => position as u64,
+        });
+
+        stack.push(position);
+    }
+
+    viewing_distances
+}
+
+// Applies a line sweep in both directions of every row and every column, and
+// combines the four results of every tree into a single map.
+fn sweep_grid<T: Copy>(
+    grid: &[Vec<u32>],
+    initial_value: T,
+    sweep: fn(&[u32]) -> Vec<T>,
+    combine: fn(T, T) -> T,
+) -> Vec<Vec<T>> {
+    let size_y = grid.len();
+    let size_x = grid.first().map_or(0, |row| row.len());
+    let mut map: Vec<Vec<T>> = vec![vec![initial_value; size_x]; size_y];
+
+    for (y, row) in grid.iter().enumerate() {
+        let mut reversed_row = row.clone();
+        reversed_row.reverse();
+
+        for (x, value) in sweep(row).into_iter().enumerate() {
+            map[y][x] = combine(map[y][x], value);
+        }
+
+        for (x, value) in sweep(&reversed_row).into_iter().enumerate() {
+            map[y][size_x - 1 - x] = combine(map[y][size_x - 1 - x], value);
+        }
+    }
+
+    for x in 0..size_x {
+        let mut column: Vec<u32> = grid.iter().map(|row| row[x]).collect();
+
+        for (y, value) in sweep(&column).into_iter().enumerate() {
+            map[y][x] = combine(map[y][x], value);
+        }
+
+        column.reverse();
+
+        for (y, value) in sweep(&column).into_iter().enumerate() {
+            map[size_y - 1 - y][x] = combine(map[size_y - 1 - y][x], value);
+        }
+    }
+
+    map
+}
+
+pub struct TreeGrid {
     size_x: usize,
     size_y: usize,
     grid: Vec<Vec<u32>>,
@@ -200,42 +319,142 @@ impl TreeGrid {
             None
         }
     }
-}
 
-pub fn part1(input: &str) -> Result<String, String> {
-    let tree_grid = TreeGrid::new(input);
-    let mut number_of_visible_trees: u64 = 0;
+    pub fn visibility_map(&self) -> Vec<Vec<bool>> {
+        sweep_grid(
+            &self.grid,
+            false,
+            line_visibility,
+            |visible, visible_from_side| visible || visible_from_side,
+        )
+    }
+
+    pub fn tree_map(&self, tree_map: TreeMap) -> Vec<Vec<u64>> {
+        match tree_map {
+            TreeMap::Visibility => self
+                .visibility_map()
+                .iter()
+                .map(|row| row.iter().map(|visible| *visible as u64).collect())
+                .collect(),
+            TreeMap::ScenicScore => self.scenic_score_map(),
+        }
+    }
+
+    // Scenic scores are products of four distances, so they are shown on a
+    // logarithmic scale to keep the smaller scores apart.
+    fn intensity_map(&self, tree_map: TreeMap) -> Vec<Vec<f64>> {
+        let values = self.tree_map(tree_map);
+        let highest_value = values.iter().flatten().max().copied().unwrap_or(0);
+
+        values
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|value| match (tree_map, highest_value) {
+                        (_, 0) => 0.0,
+                        (TreeMap::Visibility, _) => *value as f64,
+                        (TreeMap::ScenicScore, _) => {
+                            (*value as f64).ln_1p() / (highest_value as f64).ln_1p()
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn to_csv(&self, tree_map: TreeMap) -> String {
+        self.tree_map(tree_map)
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+                    + "\n"
+            })
+            .collect()
+    }
+
+    // Every tree is shown with its height over a background colored after its value.
+    pub fn to_terminal(&self, tree_map: TreeMap) -> String {
+        let intensity_map = self.intensity_map(tree_map);
+        let mut output = String::new();
 
-    for y in 0..tree_grid.size_y {
-        for x in 0..tree_grid.size_x {
-            match tree_grid.is_visible(x, y) {
-                Some(true) => number_of_visible_trees += 1,
-                _ => continue,
+        for (row, intensities) in self.grid.iter().zip(intensity_map.iter()) {
+            for (height, intensity) in row.iter().zip(intensities.iter()) {
+                let [r, g, b] = heat_color(*intensity);
+
+                output += &format!("\x1b[48;2;{};{};{}m{}", r, g, b, height);
             }
+
+            output += "\x1b[0m\n";
         }
+
+        output
     }
 
+    pub fn to_image(
+        &self,
+        tree_map: TreeMap,
+        format: ImageFormat,
+        pixel_scale: usize,
+    ) -> Result<Vec<u8>, String> {
+        let pixels: Vec<[u8; 3]> = self
+            .intensity_map(tree_map)
+            .iter()
+            .flatten()
+            .map(|intensity| match tree_map {
+                TreeMap::Visibility => [(255.0 * intensity) as u8; 3],
+                TreeMap::ScenicScore => heat_color(*intensity),
+            })
+            .collect();
+
+        image::encode_rgb(
+            format,
+            self.size_x * pixel_scale,
+            self.size_y * pixel_scale,
+            &image::scale(self.size_x, self.size_y, &pixels, pixel_scale),
+        )
+    }
+
+    pub fn scenic_score_map(&self) -> Vec<Vec<u64>> {
+        sweep_grid(
+            &self.grid,
+            1,
+            line_viewing_distances,
+            |scenic_score, viewing_distance| scenic_score * viewing_distance,
+        )
+    }
+}
+
+pub fn part1(input: &str) -> Result<String, String> {
+    let tree_grid = TreeGrid::new(input);
+
+    let number_of_visible_trees = tree_grid
+        .visibility_map()
+        .iter()
+        .flatten()
+        .filter(|visible| **visible)
+        .count();
+
     Ok(number_of_visible_trees.to_string())
 }
 
 pub fn part2(input: &str) -> Result<String, String> {
     let tree_grid = TreeGrid::new(input);
-    let mut scenic_scores: Vec<u64> = Vec::new();
 
-    for y in 0..tree_grid.size_y {
-        for x in 0..tree_grid.size_x {
-            scenic_scores.push(tree_grid.scenic_score(x, y).unwrap());
-        }
+    match tree_grid.scenic_score_map().iter().flatten().max() {
+        Some(scenic_score) => Ok(scenic_score.to_string()),
+        None => Err("No tree found in the grid".to_string()),
     }
-
-    scenic_scores.sort();
-
-    Ok(scenic_scores.pop().unwrap().to_string())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day8::{part1, part2, TreeGrid};
+    use crate::day8::{
+        heat_color, line_viewing_distances, line_visibility, part1, part2, TreeGrid, TreeMap,
+    };
+    use crate::image::ImageFormat;
 
     static TEST_INPUT: &str = "30373
 25512
@@ -336,4 +555,147 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), Ok(8.to_string()));
     }
+
+    #[test]
+    fn test_line_visibility() {
+        assert_eq!(
+            line_visibility(&[2, 5, 5, 1, 6]),
+            vec![true, true, false, false, true]
+        );
+    }
+
+    #[test]
+    fn test_line_viewing_distances() {
+        assert_eq!(
+            line_viewing_distances(&[3, 3, 5, 4, 9]),
+            vec![0, 1, 2, 1, 4]
+        );
+        assert_eq!(line_viewing_distances(&[]), vec![]);
+    }
+
+    #[test]
+    fn test_treegrid_visibility_map() {
+        assert_eq!(
+            TreeGrid::new(TEST_INPUT).visibility_map(),
+            vec![
+                vec![true, true, true, true, true],
+                vec![true, true, true, false, true],
+                vec![true, true, false, true, true],
+                vec![true, false, true, false, true],
+                vec![true, true, true, true, true]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_treegrid_scenic_score_map() {
+        assert_eq!(
+            TreeGrid::new(TEST_INPUT).scenic_score_map(),
+            vec![
+                vec![0, 0, 0, 0, 0],
+                vec![0, 1, 4, 1, 0],
+                vec![0, 6, 1, 2, 0],
+                vec![0, 1, 8, 3, 0],
+                vec![0, 0, 0, 0, 0]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_treegrid_maps_match_per_tree_computation() {
+        // Non-square forest with equal heights and tall trees hiding others.
+        let input = "3037354920
+2551209613
+6533299041
+3354905798
+3549928780
+9111111119
+0000000000
+";
+        let tree_grid = TreeGrid::new(input);
+        let visibility_map = tree_grid.visibility_map();
+        let scenic_score_map = tree_grid.scenic_score_map();
+
+        for y in 0..7 {
+            for x in 0..10 {
+                assert_eq!(tree_grid.is_visible(x, y), Some(visibility_map[y][x]));
+                assert_eq!(tree_grid.scenic_score(x, y), Some(scenic_score_map[y][x]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_tree_map_from_str() {
+        assert_eq!("visibility".parse::<TreeMap>(), Ok(TreeMap::Visibility));
+        assert_eq!("scenic-score".parse::<TreeMap>(), Ok(TreeMap::ScenicScore));
+        assert_eq!(
+            "height".parse::<TreeMap>(),
+            Err("Unsupported tree map: height".to_string())
+        );
+    }
+
+    #[test]
+    fn test_heat_color() {
+        assert_eq!(heat_color(0.0), [0, 0, 128]);
+        assert_eq!(heat_color(0.5), [255, 0, 0]);
+        assert_eq!(heat_color(1.0), [255, 255, 0]);
+        assert_eq!(heat_color(2.0), [255, 255, 0]);
+    }
+
+    #[test]
+    fn test_treegrid_to_csv() {
+        let tree_grid = TreeGrid::new(TEST_INPUT);
+
+        assert_eq!(
+            tree_grid.to_csv(TreeMap::Visibility),
+            "1,1,1,1,1\n1,1,1,0,1\n1,1,0,1,1\n1,0,1,0,1\n1,1,1,1,1\n"
+        );
+        assert_eq!(
+            tree_grid.to_csv(TreeMap::ScenicScore),
+            "0,0,0,0,0\n0,1,4,1,0\n0,6,1,2,0\n0,1,8,3,0\n0,0,0,0,0\n"
+        );
+    }
+
+    #[test]
+    fn test_treegrid_to_terminal() {
+        let tree_grid = TreeGrid::new(TEST_INPUT_WITH_TREE_ONLY_VISIBLE_FROM_LEFT);
+        let output = tree_grid.to_terminal(TreeMap::Visibility);
+
+        assert_eq!(output.lines().count(), 3);
+        assert!(output.starts_with("\x1b[48;2;255;255;0m5"));
+        assert!(output
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("\x1b[48;2;255;255;0m1\x1b[48;2;0;0;128m1"));
+        assert!(output.ends_with("\x1b[0m\n"));
+    }
+
+    #[test]
+    fn test_treegrid_to_image() {
+        let tree_grid = TreeGrid::new(TEST_INPUT);
+
+        assert_eq!(
+            tree_grid
+                .to_image(TreeMap::Visibility, ImageFormat::Pgm, 1)
+                .unwrap()[11..],
+            [
+                255, 255, 255, 255, 255, 255, 255, 255, 0, 255, 255, 255, 0, 255, 255, 255, 0, 255,
+                0, 255, 255, 255, 255, 255, 255
+            ]
+        );
+
+        let image = tree_grid
+            .to_image(TreeMap::ScenicScore, ImageFormat::Ppm, 2)
+            .unwrap();
+
+        assert!(image.starts_with(b"P6\n10 10\n255\n"));
+        assert_eq!(image.len(), 13 + 10 * 10 * 3);
+        // The best spot for the tree house is the brightest one.
+        assert_eq!(image[13 + (6 * 10 + 4) * 3..][..3], [255, 255, 0]);
+        assert_eq!(
+            TreeGrid::new("\n").to_image(TreeMap::Visibility, ImageFormat::Png, 1),
+            Err("Cannot make an empty 0x1 image".to_string())
+        );
+    }
 }
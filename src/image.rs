@@ -0,0 +1,425 @@
+// Dependency-free encoders for the Netpbm (PBM, PGM, PPM) and PNG image formats.
+// PNG image data is stored without compression, which keeps the encoder short
+// while still producing files readable by any image viewer.
+
+use std::path::Path;
+use std::str::FromStr;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+const MAX_STORED_BLOCK_LENGTH: usize = 65_535;
+// PNG widths and heights are 4-byte integers of at most 2^31 - 1.
+const MAX_PNG_DIMENSION: usize = i32::MAX as usize;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageFormat {
+    Pbm,
+    Pgm,
+    Ppm,
+    Png,
+}
+
+impl FromStr for ImageFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.to_ascii_lowercase().as_str() {
+            "pbm" => Ok(ImageFormat::Pbm),
+            "pgm" => Ok(ImageFormat::Pgm),
+            "ppm" => Ok(ImageFormat::Ppm),
+            "png" => Ok(ImageFormat::Png),
+            other => Err(format!("Unsupported image format: {}", other)),
+        }
+    }
+}
+
+impl ImageFormat {
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) => extension.parse::<ImageFormat>(),
+            None => Err(format!(
+                "Could not guess image format of '{}'",
+                path.display()
+            )),
+        }
+    }
+
+    pub fn extension(&self) -> &str {
+        match self {
+            ImageFormat::Pbm => "pbm",
+            ImageFormat::Pgm => "pgm",
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+        }
+    }
+}
+
+// Repeats every pixel `scale` times horizontally and vertically.
+pub fn scale<T: Copy>(width: usize, height: usize, pixels: &[T], scale: usize) -> Vec<T> {
+    let mut scaled_pixels: Vec<T> = Vec::with_capacity(pixels.len() * scale * scale);
+
+    if width == 0 {
+        return scaled_pixels;
+    }
+
+    for row in pixels.chunks(width).take(height) {
+        for _ in 0..scale {
+            for pixel in row {
+                for _ in 0..scale {
+                    scaled_pixels.push(*pixel);
+                }
+            }
+        }
+    }
+
+    scaled_pixels
+}
+
+fn check_number_of_pixels<T>(width: usize, height: usize, pixels: &[T]) -> Result<(), String> {
+    if width == 0 || height == 0 {
+        return Err(format!("Cannot make an empty {}x{} image", width, height));
+    }
+
+    match width.checked_mul(height) {
+        Some(number_of_pixels) if pixels.len() >= number_of_pixels => Ok(()),
+        _ => Err(format!(
+            "Not enough pixels for a {}x{} image: {}",
+            width,
+            height,
+            pixels.len()
+        )),
+    }
+}
+
+// Black pixels are the ones darker than mid-gray, as PBM is a bilevel format.
+pub fn pbm(width: usize, height: usize, pixels: &[u8]) -> Result<Vec<u8>, String> {
+    check_number_of_pixels(width, height, pixels)?;
+
+    let mut image = format!("P4\n{} {}\n", width, height).into_bytes();
+
+    for row in pixels.chunks(width).take(height) {
+        for bits in row.chunks(8) {
+            let mut byte: u8 = 0;
+
+            for (index, pixel) in bits.iter().enumerate() {
+                if *pixel < 128 {
+                    byte |= 0x80 >> index;
+                }
+            }
+
+            image.push(byte);
+        }
+    }
+
+    Ok(image)
+}
+
+pub fn pgm(width: usize, height: usize, pixels: &[u8]) -> Result<Vec<u8>, String> {
+    check_number_of_pixels(width, height, pixels)?;
+
+    let mut image = format!("P5\n{} {}\n255\n", width, height).into_bytes();
+
+    image.extend_from_slice(&pixels[..width * height]);
+    Ok(image)
+}
+
+pub fn ppm(width: usize, height: usize, pixels: &[[u8; 3]]) -> Result<Vec<u8>, String> {
+    check_number_of_pixels(width, height, pixels)?;
+
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+
+    for pixel in &pixels[..width * height] {
+        image.extend_from_slice(pixel);
+    }
+
+    Ok(image)
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0xFFFF_FFFF;
+
+    for byte in data {
+        crc ^= *byte as u32;
+
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let mut a: u32 = 1;
+    let mut b: u32 = 0;
+
+    for byte in data {
+        a = (a + *byte as u32) % 65_521;
+        b = (b + a) % 65_521;
+    }
+
+    (b << 16) | a
+}
+
+fn png_chunk(image: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    let mut chunk: Vec<u8> = Vec::with_capacity(data.len() + 4);
+
+    chunk.extend_from_slice(chunk_type);
+    chunk.extend_from_slice(data);
+
+    image.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    image.extend_from_slice(&chunk);
+    image.extend_from_slice(&crc32(&chunk).to_be_bytes());
+}
+
+// Zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream: Vec<u8> = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = match data.is_empty() {
+        true => vec![data],
+        false => data.chunks(MAX_STORED_BLOCK_LENGTH).collect(),
+    };
+
+    for (index, block) in blocks.iter().enumerate() {
+        let length = block.len() as u16;
+
+        stream.push((index == blocks.len() - 1) as u8);
+        stream.extend_from_slice(&length.to_le_bytes());
+        stream.extend_from_slice(&(!length).to_le_bytes());
+        stream.extend_from_slice(block);
+    }
+
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+fn check_png_dimensions(width: usize, height: usize) -> Result<(), String> {
+    match width > MAX_PNG_DIMENSION || height > MAX_PNG_DIMENSION {
+        true => Err(format!(
+            "Too large for a PNG image: {}x{}, the limit is {}x{}",
+            width, height, MAX_PNG_DIMENSION, MAX_PNG_DIMENSION
+        )),
+        false => Ok(()),
+    }
+}
+
+fn png(
+    width: usize,
+    height: usize,
+    color_type: u8,
+    bytes_per_pixel: usize,
+    data: &[u8],
+) -> Vec<u8> {
+    let mut image: Vec<u8> = PNG_SIGNATURE.to_vec();
+    let mut header: Vec<u8> = Vec::with_capacity(13);
+    let mut scanlines: Vec<u8> = Vec::with_capacity((width * bytes_per_pixel + 1) * height);
+
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    header.extend_from_slice(&[8, color_type, 0, 0, 0]);
+
+    for row in data.chunks(width * bytes_per_pixel).take(height) {
+        // Filter type 0 (None) for every scanline.
+        scanlines.push(0);
+        scanlines.extend_from_slice(row);
+    }
+
+    png_chunk(&mut image, b"IHDR", &header);
+    png_chunk(&mut image, b"IDAT", &zlib_stored(&scanlines));
+    png_chunk(&mut image, b"IEND", &[]);
+
+    image
+}
+
+pub fn png_grayscale(width: usize, height: usize, pixels: &[u8]) -> Result<Vec<u8>, String> {
+    check_png_dimensions(width, height)?;
+    check_number_of_pixels(width, height, pixels)?;
+
+    Ok(png(width, height, 0, 1, pixels))
+}
+
+pub fn png_rgb(width: usize, height: usize, pixels: &[[u8; 3]]) -> Result<Vec<u8>, String> {
+    check_png_dimensions(width, height)?;
+    check_number_of_pixels(width, height, pixels)?;
+
+    Ok(png(width, height, 2, 3, &pixels.concat()))
+}
+
+pub fn encode_grayscale(
+    format: ImageFormat,
+    width: usize,
+    height: usize,
+    pixels: &[u8],
+) -> Result<Vec<u8>, String> {
+    match format {
+        ImageFormat::Pbm => pbm(width, height, pixels),
+        ImageFormat::Pgm => pgm(width, height, pixels),
+        ImageFormat::Ppm => ppm(
+            width,
+            height,
+            &pixels
+                .iter()
+                .map(|pixel| [*pixel; 3])
+                .collect::<Vec<[u8; 3]>>(),
+        ),
+        ImageFormat::Png => png_grayscale(width, height, pixels),
+    }
+}
+
+// Formats without colors get the luma of every pixel (ITU-R BT.601).
+pub fn encode_rgb(
+    format: ImageFormat,
+    width: usize,
+    height: usize,
+    pixels: &[[u8; 3]],
+) -> Result<Vec<u8>, String> {
+    match format {
+        ImageFormat::Pbm | ImageFormat::Pgm => encode_grayscale(
+            format,
+            width,
+            height,
+            &pixels
+                .iter()
+                .map(|[r, g, b]| {
+                    ((299 * *r as u32 + 587 * *g as u32 + 114 * *b as u32) / 1000) as u8
+                })
+                .collect::<Vec<u8>>(),
+        ),
+        ImageFormat::Ppm => ppm(width, height, pixels),
+        ImageFormat::Png => png_rgb(width, height, pixels),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::image::{
+        adler32, crc32, encode_grayscale, encode_rgb, pbm, pgm, png_grayscale, ppm, scale,
+        ImageFormat,
+    };
+    use std::path::Path;
+
+    #[test]
+    fn test_image_format() {
+        assert_eq!("PNG".parse::<ImageFormat>(), Ok(ImageFormat::Png));
+        assert_eq!(
+            "gif".parse::<ImageFormat>(),
+            Err("Unsupported image format: gif".to_string())
+        );
+        assert_eq!(
+            ImageFormat::from_path(Path::new("crt.pbm")),
+            Ok(ImageFormat::Pbm)
+        );
+        assert_eq!(
+            ImageFormat::from_path(Path::new("crt")),
+            Err("Could not guess image format of 'crt'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_scale() {
+        assert_eq!(scale(2, 1, &[1, 2], 2), vec![1, 1, 2, 2, 1, 1, 2, 2]);
+        assert_eq!(scale(0, 1, &[1, 2], 2), vec![]);
+    }
+
+    #[test]
+    fn test_pbm() {
+        assert_eq!(
+            pbm(10, 1, &[0, 255, 0, 255, 0, 255, 0, 255, 0, 0]),
+            Ok([b"P4\n10 1\n".to_vec(), vec![0b1010_1010, 0b1100_0000]].concat())
+        );
+    }
+
+    #[test]
+    fn test_pgm_and_ppm() {
+        assert_eq!(
+            pgm(2, 1, &[0, 255]),
+            Ok([b"P5\n2 1\n255\n".to_vec(), vec![0, 255]].concat())
+        );
+        assert_eq!(
+            ppm(1, 1, &[[1, 2, 3]]),
+            Ok([b"P6\n1 1\n255\n".to_vec(), vec![1, 2, 3]].concat())
+        );
+        assert_eq!(
+            encode_grayscale(ImageFormat::Ppm, 1, 1, &[7]),
+            Ok([b"P6\n1 1\n255\n".to_vec(), vec![7, 7, 7]].concat())
+        );
+    }
+
+    #[test]
+    fn test_not_enough_pixels() {
+        let error = Err("Not enough pixels for a 2x2 image: 3".to_string());
+
+        assert_eq!(pbm(2, 2, &[0, 255, 0]), error);
+        assert_eq!(pgm(2, 2, &[0, 255, 0]), error);
+        assert_eq!(ppm(2, 2, &[[0; 3]; 3]), error);
+        assert_eq!(png_grayscale(2, 2, &[0, 255, 0]), error);
+        assert_eq!(
+            encode_rgb(ImageFormat::Ppm, usize::MAX, 2, &[]),
+            Err(format!("Not enough pixels for a {}x2 image: 0", usize::MAX))
+        );
+    }
+
+    #[test]
+    fn test_bad_dimensions() {
+        for format in [
+            ImageFormat::Pbm,
+            ImageFormat::Pgm,
+            ImageFormat::Ppm,
+            ImageFormat::Png,
+        ] {
+            assert_eq!(
+                encode_grayscale(format, 0, 2, &[0]),
+                Err("Cannot make an empty 0x2 image".to_string())
+            );
+            assert_eq!(
+                encode_rgb(format, 2, 0, &[[0; 3]]),
+                Err("Cannot make an empty 2x0 image".to_string())
+            );
+        }
+
+        assert_eq!(
+            png_grayscale(1 << 32, 1, &[]),
+            Err(format!(
+                "Too large for a PNG image: {}x1, the limit is {}x{}",
+                1_usize << 32,
+                i32::MAX,
+                i32::MAX
+            ))
+        );
+        assert!(png_grayscale(1, 1 << 31, &[]).is_err());
+    }
+
+    #[test]
+    fn test_encode_rgb() {
+        assert_eq!(
+            encode_rgb(ImageFormat::Pgm, 2, 1, &[[255, 255, 255], [255, 0, 0]]),
+            Ok([b"P5\n2 1\n255\n".to_vec(), vec![255, 76]].concat())
+        );
+        assert_eq!(
+            encode_rgb(ImageFormat::Png, 1, 1, &[[1, 2, 3]]).unwrap()[25],
+            2
+        );
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn test_png_grayscale() {
+        let image = png_grayscale(2, 2, &[0, 255, 255, 0]).unwrap();
+
+        assert_eq!(image[..8], [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]);
+        assert_eq!(image[12..16], *b"IHDR");
+        assert_eq!(image[16..25], [0, 0, 0, 2, 0, 0, 0, 2, 8]);
+        assert_eq!(
+            image[image.len() - 12..],
+            [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]
+        );
+    }
+}
@@ -1,32 +1,263 @@
-use clap::Parser;
+use aoc_rust_2022::day5::{CraneModel, MoveValidation};
+use aoc_rust_2022::day7::CleanupPolicy;
+use aoc_rust_2022::day8::TreeMap;
+use aoc_rust_2022::image::ImageFormat;
+use aoc_rust_2022::{day1, day10, day2, day3, day4, day5, day6, day7, day8, day9};
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgGroup, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
 use std::fs;
-use std::path::PathBuf;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
-mod day1;
-mod day10;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-
 #[derive(Parser)]
 #[command(name = "advent-of-code-2022")]
 #[command(about = "Calculate solutions for Advent of Code 2022 using Rust and the provided input", long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+// Every option showing something instead of solving the puzzle is a mode of its own.
+#[command(group(ArgGroup::new("mode").multiple(false).args(MODES)))]
 struct Cli {
-    input_path: String,
-    #[arg(short, long)]
+    #[command(subcommand)]
+    command: Option<Command>,
+    // Required unless a subcommand is given.
+    #[arg(required = true)]
+    input_path: Option<String>,
+    #[arg(short, long, required = true)]
     // TODO: Make this parameter optional and if not provided, solve all days.
-    // day: Option<u8>
-    day: u8,
-    #[arg(short, long)]
+    day: Option<u8>,
+    #[arg(short, long, required = true)]
     // TODO: Make this parameter optional and if not provided, solve all parts of a given day.
-    // part: Option<u8>
-    part: u8,
+    part: Option<u8>,
+    /// Start an interactive debugger over the puzzle input instead of solving it (Day 10 only)
+    #[arg(long)]
+    debug: bool,
+    /// Width of the CRT in pixels (Day 10 only)
+    #[arg(long)]
+    crt_width: Option<usize>,
+    /// Height of the CRT in pixels (Day 10 only)
+    #[arg(long)]
+    crt_height: Option<usize>,
+    /// Width of the sprite in pixels (Day 10 only)
+    #[arg(long)]
+    sprite_width: Option<usize>,
+    /// First cycle for which the signal strength is sampled (Day 10 only)
+    #[arg(long)]
+    sample_start: Option<usize>,
+    /// Number of cycles between two signal strength samples (Day 10 only)
+    #[arg(long)]
+    sample_interval: Option<usize>,
+    /// Show the reconstructed filesystem as a tree instead of solving the puzzle (Day 7 only)
+    #[arg(long)]
+    tree: bool,
+    /// Show the size of every directory like 'du' instead of solving the puzzle (Day 7 only)
+    #[arg(long)]
+    du: bool,
+    /// Sort the directories shown by --du from the largest to the smallest (Day 7 only)
+    #[arg(long, requires = "du")]
+    sort_by_size: bool,
+    /// Show the sizes of the directories shown by --du in powers of 1024 (Day 7 only)
+    #[arg(long, requires = "du")]
+    human_readable: bool,
+    /// Rules of the game (rock-paper-scissors or rock-paper-scissors-lizard-spock) (Day 2 only)
+    #[arg(long, default_value = "rock-paper-scissors")]
+    rules: day2::Rules,
+    /// Report invalid rucksacks and groups as warnings instead of failing (Day 3 only)
+    #[arg(long)]
+    lenient: bool,
+    /// Number of rucksacks in a group of elves (Day 3 only)
+    #[arg(long, default_value_t = day3::GROUP_SIZE)]
+    group_size: usize,
+    /// Show every item type shared by each group instead of solving the puzzle (Day 3 only)
+    #[arg(long)]
+    shared_items: bool,
+    /// Show which sections are covered by the assignments instead of solving the puzzle (Day 4 only)
+    #[arg(long)]
+    coverage: bool,
+    /// Show the number of overlapping pairs of every group instead of solving the puzzle (Day 4 only)
+    #[arg(long)]
+    overlaps: bool,
+    /// Crane used to rearrange the crates (9000, 9001, batch:N or alternating), overriding the one of the part (Day 5 only)
+    #[arg(long)]
+    crane: Option<CraneModel>,
+    /// Show the stacks after every move instead of solving the puzzle (Day 5 only)
+    #[arg(long)]
+    replay: bool,
+    /// Show the stacks right after this move instead of solving the puzzle (Day 5 only)
+    #[arg(long)]
+    after_move: Option<usize>,
+    /// Show the messages of the datastream and their payload instead of solving the puzzle (Day 6 only)
+    #[arg(long)]
+    messages: bool,
+    /// Ignore the moves that cannot be parsed and move at most the available crates (Day 5 only)
+    #[arg(long)]
+    clamp: bool,
+    /// Total disk space of the device (Day 7 only)
+    #[arg(long)]
+    total_disk_space: Option<u64>,
+    /// Unused space required to run the update (Day 7 only)
+    #[arg(long)]
+    unused_space_required: Option<u64>,
+    /// Largest directory size counted in part 1 (Day 7 only)
+    #[arg(long)]
+    max_directory_size: Option<u64>,
+    /// Show the directories to delete (smallest-directory or fewest-directories) instead of solving the puzzle (Day 7 only)
+    #[arg(long)]
+    cleanup_plan: Option<CleanupPolicy>,
+    /// Show a map of the trees (visibility or scenic-score) instead of solving the puzzle (Day 8 only)
+    // Also picks the map exported by --image.
+    #[arg(long, conflicts_with_all = MODES.iter().copied().filter(|mode| *mode != "image"))]
+    tree_map: Option<TreeMap>,
+    /// Show the tree map as CSV instead of colored text (Day 8 only)
+    #[arg(long, requires = "tree_map", conflicts_with = "image")]
+    csv: bool,
+    /// Export the puzzle output as an image to this path instead of solving it (Day 8 and 10 only)
+    #[arg(long)]
+    image: Option<PathBuf>,
+    /// Format of the exported image (pbm, pgm, ppm or png), guessed from the image path by default
+    #[arg(long, requires = "image")]
+    image_format: Option<ImageFormat>,
+    /// Size in image pixels of every exported pixel
+    #[arg(long, default_value_t = 1, requires = "image")]
+    pixel_scale: usize,
+    /// Export one image per cycle, numbered after the image path (Day 10 only)
+    #[arg(long, requires = "image")]
+    frames: bool,
+}
+
+const MODES: [&str; 11] = [
+    "debug",
+    "image",
+    "tree",
+    "du",
+    "shared_items",
+    "coverage",
+    "overlaps",
+    "replay",
+    "after_move",
+    "messages",
+    "cleanup_plan",
+];
+
+// Days of the options which only apply to some days.
+const DAY_OPTIONS: [(&str, &[u8]); 31] = [
+    ("rules", &[2]),
+    ("lenient", &[3]),
+    ("group_size", &[3]),
+    ("shared_items", &[3]),
+    ("coverage", &[4]),
+    ("overlaps", &[4]),
+    ("crane", &[5]),
+    ("replay", &[5]),
+    ("after_move", &[5]),
+    ("clamp", &[5]),
+    ("messages", &[6]),
+    ("tree", &[7]),
+    ("du", &[7]),
+    ("sort_by_size", &[7]),
+    ("human_readable", &[7]),
+    ("total_disk_space", &[7]),
+    ("unused_space_required", &[7]),
+    ("max_directory_size", &[7]),
+    ("cleanup_plan", &[7]),
+    ("tree_map", &[8]),
+    ("csv", &[8]),
+    ("image", &[8, 10]),
+    ("image_format", &[8, 10]),
+    ("pixel_scale", &[8, 10]),
+    ("frames", &[10]),
+    ("debug", &[10]),
+    ("crt_width", &[10]),
+    ("crt_height", &[10]),
+    ("sprite_width", &[10]),
+    ("sample_start", &[10]),
+    ("sample_interval", &[10]),
+];
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print a Day 7 terminal output transcript exploring a local directory
+    Transcript {
+        directory: PathBuf,
+        /// Also list the size of every directory itself, so that directory sizes match 'du --apparent-size -b'
+        #[arg(long)]
+        apparent_size: bool,
+    },
+    /// Print the Day 6 markers of a datastream read from the standard input as soon as they are detected
+    Markers {
+        /// Kind of marker to detect (start-of-packet or start-of-message)
+        #[arg(long, default_value = "start-of-packet")]
+        kind: day6::MarkerKind,
+    },
+}
+
+impl Cli {
+    fn crane_model(&self, part: u8) -> CraneModel {
+        match (self.crane, part) {
+            (Some(model), _) => model,
+            (None, 1) => CraneModel::CrateMover9000,
+            (None, _) => CraneModel::CrateMover9001,
+        }
+    }
+
+    fn rucksack_validation(&self) -> day3::Validation {
+        match self.lenient {
+            true => day3::Validation::Lenient,
+            false => day3::Validation::Strict,
+        }
+    }
+
+    fn move_validation(&self) -> MoveValidation {
+        match self.clamp {
+            true => MoveValidation::Clamp,
+            false => MoveValidation::Strict,
+        }
+    }
+
+    fn disk_config(&self) -> day7::DiskConfig {
+        let default = day7::DiskConfig::default();
+
+        day7::DiskConfig {
+            total_disk_space: self.total_disk_space.unwrap_or(default.total_disk_space),
+            unused_space_required: self
+                .unused_space_required
+                .unwrap_or(default.unused_space_required),
+            max_directory_size_to_consider: self
+                .max_directory_size
+                .unwrap_or(default.max_directory_size_to_consider),
+        }
+    }
+
+    fn crt_config(&self) -> day10::CrtConfig {
+        let default = day10::CrtConfig::default();
+
+        day10::CrtConfig {
+            width: self.crt_width.unwrap_or(default.width),
+            height: self.crt_height.unwrap_or(default.height),
+            sprite_width: self.sprite_width.unwrap_or(default.sprite_width),
+            sample_start: self.sample_start.unwrap_or(default.sample_start),
+            sample_interval: self.sample_interval.unwrap_or(default.sample_interval),
+        }
+    }
+}
+
+// Options of another day would otherwise be left out without notice.
+fn check_day_options(matches: &ArgMatches, day: u8) -> Result<(), String> {
+    for (id, days) in DAY_OPTIONS {
+        if matches.value_source(id) == Some(ValueSource::CommandLine) && !days.contains(&day) {
+            return Err(format!(
+                "--{} cannot be used with day {} (Day {} only)",
+                id.replace('_', "-"),
+                day,
+                days.iter()
+                    .map(|day| day.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" and ")
+            ));
+        }
+    }
+
+    Ok(())
 }
 
 fn load_file(filename: PathBuf) -> Option<std::string::String> {
@@ -41,48 +272,382 @@ fn load_file(filename: PathBuf) -> Option<std::string::String> {
     }
 }
 
-type SolverFn = fn(&str) -> Result<String, String>;
+fn image_format(cli: &Cli, path: &Path) -> Result<ImageFormat, String> {
+    match cli.image_format {
+        Some(format) => Ok(format),
+        None => ImageFormat::from_path(path),
+    }
+}
+
+// Turns 'crt.png' into 'crt-0001.png' for the first frame.
+fn frame_path(path: &Path, format: ImageFormat, number: usize) -> PathBuf {
+    let stem = match path.file_stem() {
+        Some(stem) => stem.to_string_lossy().to_string(),
+        None => "frame".to_string(),
+    };
+
+    path.with_file_name(format!("{}-{:04}.{}", stem, number, format.extension()))
+}
+
+fn export_crt(cli: &Cli, path: &Path, input: &str) -> Result<usize, String> {
+    let format = image_format(cli, path)?;
+    let config = cli.crt_config();
+
+    if cli.pixel_scale == 0 {
+        return Err("Pixel scale must be at least 1".to_string());
+    }
+
+    let write_frame = |frame_path: PathBuf, frame: &day10::Frame| {
+        fs::write(&frame_path, frame.encode(format, cli.pixel_scale)?)
+            .map_err(|err| format!("Could not write image '{}'. {}", frame_path.display(), err))
+    };
+
+    match cli.frames {
+        true => day10::render_frames(input, &config, |number, frame| {
+            write_frame(frame_path(path, format, number), frame)
+        }),
+        false => {
+            write_frame(path.to_path_buf(), &day10::render(input, &config)?)?;
+            Ok(1)
+        }
+    }
+}
+
+fn export_tree_map(cli: &Cli, path: &Path, input: &str) -> Result<usize, String> {
+    let format = image_format(cli, path)?;
+    let tree_grid = day8::TreeGrid::new(input);
+
+    if cli.pixel_scale == 0 {
+        return Err("Pixel scale must be at least 1".to_string());
+    }
+
+    let image = tree_grid.to_image(
+        cli.tree_map.unwrap_or(TreeMap::ScenicScore),
+        format,
+        cli.pixel_scale,
+    )?;
+
+    match fs::write(path, image) {
+        Ok(_) => Ok(1),
+        Err(err) => Err(format!(
+            "Could not write image '{}'. {}",
+            path.display(),
+            err
+        )),
+    }
+}
+
+fn replay_procedure(cli: &Cli, part: u8, input: &str) -> Result<String, String> {
+    let mut replay = day5::Replay::new(input, cli.crane_model(part), cli.move_validation())?;
+
+    let mut output = replay.stacks().to_string();
+
+    match cli.after_move {
+        Some(number) => {
+            replay.jump_to(number)?;
+            output = replay.stacks().to_string();
+        }
+        None => {
+            for state in replay.by_ref() {
+                let (step, stacks) = state?;
+
+                output += &format!("\n{}\n{}", step, stacks);
+            }
+        }
+    }
+
+    for warning in replay.warnings() {
+        println!("Warning: {}", warning);
+    }
+
+    Ok(output)
+}
+
+type SolverFn = Box<dyn Fn(&str) -> Result<String, String>>;
 
-fn solve(day: u8, part: u8) -> Result<SolverFn, (u8, u8)> {
+fn solve(cli: &Cli, day: u8, part: u8) -> Result<SolverFn, (u8, u8)> {
     match (day, part) {
-        (1, 1) => Ok(day1::part1),
-        (1, 2) => Ok(day1::part2),
-        (2, 1) => Ok(day2::part1),
-        (2, 2) => Ok(day2::part2),
-        (3, 1) => Ok(day3::part1),
-        (3, 2) => Ok(day3::part2),
-        (4, 1) => Ok(day4::part1),
-        (4, 2) => Ok(day4::part2),
-        (5, 1) => Ok(day5::part1),
-        (5, 2) => Ok(day5::part2),
-        (6, 1) => Ok(day6::part1),
-        (6, 2) => Ok(day6::part2),
-        (7, 1) => Ok(day7::part1),
-        (7, 2) => Ok(day7::part2),
-        (8, 1) => Ok(day8::part1),
-        (8, 2) => Ok(day8::part2),
-        (9, 1) => Ok(day9::part1),
-        (9, 2) => Ok(day9::part2),
-        (10, 1) => Ok(day10::part1),
-        (10, 2) => Ok(day10::part2),
-        (_, _) => Err((day, part)),
+        (1, 1) => Ok(Box::new(day1::part1)),
+        (1, 2) => Ok(Box::new(day1::part2)),
+        (2, 1 | 2) => {
+            let decoding = match part {
+                1 => day2::Decoding::Shape,
+                _ => day2::Decoding::Outcome,
+            };
+            let rules = cli.rules.clone();
+            Ok(Box::new(move |input| {
+                let game = day2::Game::new(rules.clone(), decoding)?;
+                Ok(game.total_score(input).to_string())
+            }))
+        }
+        (3, 1 | 2) => {
+            let validation = cli.rucksack_validation();
+            let group_size = cli.group_size;
+            Ok(Box::new(move |input| {
+                let priorities = match part {
+                    1 => day3::compartment_priorities(input, validation)?,
+                    _ => day3::group_priorities(input, group_size, validation)?,
+                };
+
+                for warning in &priorities.warnings {
+                    println!("Warning: {}", warning);
+                }
+
+                Ok(priorities.sum.to_string())
+            }))
+        }
+        (4, 1) => Ok(Box::new(day4::part1)),
+        (4, 2) => Ok(Box::new(day4::part2)),
+        (5, 1 | 2) => {
+            let model = cli.crane_model(part);
+            let validation = cli.move_validation();
+            Ok(Box::new(move |input| {
+                let rearrangement = day5::rearrange(input, model.build().as_mut(), validation)?;
+
+                for warning in &rearrangement.warnings {
+                    println!("Warning: {}", warning);
+                }
+
+                Ok(rearrangement.stacks.top_crates())
+            }))
+        }
+        (6, 1) => Ok(Box::new(day6::part1)),
+        (6, 2) => Ok(Box::new(day6::part2)),
+        (7, 1) => {
+            let config = cli.disk_config();
+            Ok(Box::new(move |input| {
+                day7::part1_with_config(input, &config)
+            }))
+        }
+        (7, 2) => {
+            let config = cli.disk_config();
+            Ok(Box::new(move |input| {
+                day7::part2_with_config(input, &config)
+            }))
+        }
+        (8, 1) => Ok(Box::new(day8::part1)),
+        (8, 2) => Ok(Box::new(day8::part2)),
+        (9, 1) => Ok(Box::new(day9::part1)),
+        (9, 2) => Ok(Box::new(day9::part2)),
+        (10, 1) => {
+            let config = cli.crt_config();
+            Ok(Box::new(move |input| {
+                day10::part1_with_config(input, &config)
+            }))
+        }
+        (10, 2) => {
+            let config = cli.crt_config();
+            Ok(Box::new(move |input| {
+                day10::part2_with_config(input, &config)
+            }))
+        }
+        (day, part) => Err((day, part)),
     }
 }
 
 fn main() {
-    let cli = Cli::parse();
+    let mut command = Cli::command();
+    let matches = command.get_matches_mut();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
+
+    match &cli.command {
+        Some(Command::Transcript {
+            directory,
+            apparent_size,
+        }) => {
+            let sizes = match apparent_size {
+                true => day7::TranscriptSizes::ApparentSize,
+                false => day7::TranscriptSizes::Files,
+            };
+
+            match day7::generate_transcript(directory, sizes) {
+                Ok(transcript) => print!("{}", transcript),
+                Err(error) => println!("A problem occured to generate the transcript: {}", error),
+            }
+            return;
+        }
+        Some(Command::Markers { kind }) => {
+            if let Err(error) = day6::detect_markers(*kind, io::stdin().lock(), &mut io::stdout()) {
+                println!("A problem occured to detect the markers: {}", error);
+            }
+            return;
+        }
+        None => (),
+    }
+
+    let (Some(input_path), Some(day), Some(part)) = (&cli.input_path, cli.day, cli.part) else {
+        return;
+    };
+
+    if let Err(error) = check_day_options(&matches, day) {
+        command.error(ErrorKind::ArgumentConflict, error).exit();
+    }
 
     let chrono_start;
     let chrono_stop;
     let solution_result;
     let mut total_time: u128 = 0;
 
-    let day_input: PathBuf = [cli.input_path, format!("day{}.txt", cli.day)]
+    let day_input: PathBuf = [input_path.clone(), format!("day{}.txt", day)]
         .iter()
         .collect();
 
     if let Some(puzzle_input) = load_file(day_input) {
-        match solve(cli.day, cli.part) {
+        if cli.debug {
+            match day {
+                10 => {
+                    if let Err(error) = day10::debug(
+                        &puzzle_input,
+                        &cli.crt_config(),
+                        io::stdin().lock(),
+                        &mut io::stdout(),
+                    ) {
+                        println!("A problem occured in the debugger: {}", error);
+                    }
+                }
+                _ => println!("No debugger available for day {}", day),
+            }
+            return;
+        }
+
+        if let Some(image_path) = &cli.image {
+            let export_result = match day {
+                8 => export_tree_map(&cli, image_path, &puzzle_input),
+                10 => export_crt(&cli, image_path, &puzzle_input),
+                _ => Err(format!("No image export available for day {}", day)),
+            };
+
+            match export_result {
+                Ok(number_of_images) => println!("Exported {} image(s)", number_of_images),
+                Err(error) => println!("A problem occured to export the image: {}", error),
+            }
+            return;
+        }
+
+        if cli.tree || cli.du {
+            match day {
+                7 => match day7::build_filesystem(&puzzle_input) {
+                    Ok(filesystem) if cli.tree => print!("{}", filesystem.render_tree()),
+                    Ok(filesystem) => {
+                        let order = match cli.sort_by_size {
+                            true => day7::DuOrder::Size,
+                            false => day7::DuOrder::Traversal,
+                        };
+
+                        print!("{}", filesystem.render_du(order, cli.human_readable))
+                    }
+                    Err(error) => println!("A problem occured to build the filesystem: {}", error),
+                },
+                _ => println!("No filesystem available for day {}", day),
+            }
+            return;
+        }
+
+        if cli.shared_items {
+            match day {
+                3 => match day3::shared_item_types(
+                    &puzzle_input,
+                    cli.group_size,
+                    cli.rucksack_validation(),
+                ) {
+                    Ok((groups, warnings)) => {
+                        for warning in warnings {
+                            println!("Warning: {}", warning);
+                        }
+
+                        for group in groups {
+                            println!("{}", group);
+                        }
+                    }
+                    Err(error) => println!("A problem occured to find the shared items: {}", error),
+                },
+                _ => println!("No shared items available for day {}", day),
+            }
+            return;
+        }
+
+        if cli.coverage {
+            match day {
+                4 => match day4::analyze_coverage(&puzzle_input) {
+                    Ok(coverage) => print!("{}", coverage),
+                    Err(error) => println!("A problem occured to analyze the coverage: {}", error),
+                },
+                _ => println!("No coverage available for day {}", day),
+            }
+            return;
+        }
+
+        if cli.overlaps {
+            match day {
+                4 => match day4::overlap_report(&puzzle_input) {
+                    Ok(report) => print!("{}", report),
+                    Err(error) => println!("A problem occured to count the overlaps: {}", error),
+                },
+                _ => println!("No overlaps available for day {}", day),
+            }
+            return;
+        }
+
+        if cli.replay || cli.after_move.is_some() {
+            match day {
+                5 => match replay_procedure(&cli, part, &puzzle_input) {
+                    Ok(output) => print!("{}", output),
+                    Err(error) => println!("A problem occured to replay the procedure: {}", error),
+                },
+                _ => println!("No replay available for day {}", day),
+            }
+            return;
+        }
+
+        if cli.messages {
+            match day {
+                6 => match day6::split_messages(
+                    puzzle_input.as_bytes(),
+                    day6::MarkerKind::StartOfMessage,
+                ) {
+                    Ok(messages) => {
+                        for message in messages {
+                            println!(
+                                "Message at {}: {:?}",
+                                message.marker.end,
+                                String::from_utf8_lossy(message.payload)
+                            );
+                        }
+                    }
+                    Err(error) => println!("A problem occured to split the messages: {}", error),
+                },
+                _ => println!("No messages available for day {}", day),
+            }
+            return;
+        }
+
+        if let Some(policy) = cli.cleanup_plan {
+            match day {
+                7 => match day7::plan_cleanup(&puzzle_input, &cli.disk_config(), policy) {
+                    Ok(plan) => print!("{}", plan),
+                    Err(error) => println!("A problem occured to plan the cleanup: {}", error),
+                },
+                _ => println!("No cleanup plan available for day {}", day),
+            }
+            return;
+        }
+
+        if let Some(tree_map) = cli.tree_map {
+            match day {
+                8 => {
+                    let tree_grid = day8::TreeGrid::new(&puzzle_input);
+
+                    match cli.csv {
+                        true => print!("{}", tree_grid.to_csv(tree_map)),
+                        false => print!("{}", tree_grid.to_terminal(tree_map)),
+                    }
+                }
+                _ => println!("No tree map available for day {}", day),
+            }
+            return;
+        }
+
+        match solve(&cli, day, part) {
             Ok(solve_function) => {
                 chrono_start = Instant::now();
                 solution_result = solve_function(&puzzle_input);
@@ -92,16 +657,16 @@ fn main() {
                 match solution_result {
                     Ok(solution) => println!(
                         "Solution of Day {}, Part {}: {}, Time: {}μs",
-                        cli.day, cli.part, solution, chrono_stop
+                        day, part, solution, chrono_stop
                     ),
                     Err(error) => println!(
                         "A problem occured to solve the problem of Day {}, Part {}: {}, Time: {}μs",
-                        cli.day, cli.part, error, chrono_stop
+                        day, part, error, chrono_stop
                     ),
                 }
                 println!("\nTotal Time: {}μs", total_time);
             }
-            Err(_) => println!("Unsupported day {} and part {}", cli.day, cli.part),
+            Err(_) => println!("Unsupported day {} and part {}", day, part),
         }
     }
 }